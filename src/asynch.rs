@@ -0,0 +1,654 @@
+//! Async driver, built on the `embedded-hal-async` traits.
+use crate::config::{AddressWidth, DataPipe, Features, NrfConfig, PayloadSize};
+use crate::error::{ConfigMismatch, TransferError};
+use crate::register_acces::{Instruction, Register};
+use crate::status::Status;
+use crate::MAX_PAYLOAD_SIZE;
+use core::fmt;
+use embedded_hal_1::digital::OutputPin;
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::digital::Wait;
+use embedded_hal_async::spi::SpiDevice;
+
+/// The async nRF24L01 driver type.
+///
+/// Uses an `embedded-hal-async` [`SpiDevice`], which drives the Chip Select pin itself, so only
+/// the Chip Enable pin is passed separately. Offers the core of [`Nrf24l01`](struct.Nrf24l01.html)
+/// for tasks that should not block while waiting for the chip, most notably
+/// [`wait_for_data()`](#method.wait_for_data), which sleeps until the IRQ pin is pulled low.
+///
+/// Only available with the `async` feature.
+///
+/// # Examples
+/// ```no_run
+/// # use nrf24_rs::doctest::*;
+/// # fn handle(_: &[u8]) {}
+/// # async fn receive() -> Result<(), Error> {
+/// # let (spi, ce, mut irq, mut delay) = peripherals();
+/// let mut chip = Nrf24l01Async::new(spi, ce, &mut delay, NrfConfig::default()).await?;
+/// chip.open_reading_pipe(DataPipe::DP1, b"Node1").await?;
+/// chip.start_listening().await?;
+/// loop {
+///     // Sleeps until the chip pulls the IRQ pin low
+///     chip.wait_for_data(&mut irq).await?;
+///     let mut buffer = [0; 32];
+///     let n = chip.read(&mut buffer).await?;
+///     handle(&buffer[..n]);
+/// }
+/// # }
+/// ```
+pub struct Nrf24l01Async<SPI, CE> {
+    spi: SPI,
+    // Chip Enable Pin
+    ce: CE,
+    // Config Register
+    config_reg: u8,
+    // Address width
+    addr_width: AddressWidth,
+    // Payload size
+    payload_size: PayloadSize,
+    // Transmission buffer
+    tx_buf: [u8; MAX_PAYLOAD_SIZE as usize + 1],
+    // Time in µs that CE is held high to start a transmission
+    ce_pulse_us: u8,
+}
+
+impl<SPI, CE> Nrf24l01Async<SPI, CE>
+where
+    SPI: SpiDevice,
+    CE: OutputPin,
+{
+    const STATUS_RESET: u8 = 0b01110000;
+
+    /// Creates a new async nrf24l01 driver with given config.
+    ///
+    /// Configures the chip in the same way as [`Nrf24l01::new()`](struct.Nrf24l01.html#method.new)
+    /// and starts it up.
+    pub async fn new<D: DelayNs>(
+        spi: SPI,
+        ce: CE,
+        delay: &mut D,
+        config: NrfConfig,
+    ) -> Result<Self, TransferError<SPI::Error, CE::Error>> {
+        let mut chip = Self::from_parts(spi, ce, &config);
+        chip.set_ce_low()?;
+        // Allow the radio to settle, see `Nrf24l01::new()`
+        delay.delay_ms(5).await;
+
+        if !chip.is_connected().await? {
+            return Err(TransferError::NotConnected);
+        }
+
+        let auto_retry = config.auto_retry;
+        chip.write_register(
+            Register::SETUP_RETR,
+            &[(auto_retry.raw_delay() << 4) | auto_retry.count()],
+        )
+        .await?;
+        chip.write_register(
+            Register::RF_SETUP,
+            &[config.data_rate.rate() | config.pa_level.level()],
+        )
+        .await?;
+        chip.set_payload_size(config.payload_size).await?;
+        // Acknowledgement payloads require dynamic payloads
+        if config.ack_payloads_enabled && chip.payload_size != PayloadSize::Dynamic {
+            chip.set_payload_size(PayloadSize::Dynamic).await?;
+        }
+        let features = chip.features().await?;
+        chip.set_features(
+            features
+                .ack_payloads(config.ack_payloads_enabled)
+                .dynamic_ack(config.dynamic_ack),
+        )
+        .await?;
+        chip.write_register(Register::SETUP_AW, &[config.addr_width.value()])
+            .await?;
+        chip.reset_status().await?;
+        chip.write_register(Register::RF_CH, &[(u8::MAX >> 1) & config.channel])
+            .await?;
+        chip.flush_rx().await?;
+        chip.flush_tx().await?;
+
+        // Enable PTX, configure the CRC & mask interrupts
+        let crc = match config.crc_encoding_scheme {
+            Some(scheme) => (1 << 3) | (scheme.scheme() << 2),
+            None => 0,
+        };
+        let config_val = crc | config.masked_interrupts.value();
+        chip.write_register(Register::CONFIG, &[config_val]).await?;
+        chip.config_reg = chip.read_register(Register::CONFIG).await?;
+
+        // Power up and wait for the oscillator to start
+        chip.config_reg |= 1 << 1;
+        chip.write_register(Register::CONFIG, &[chip.config_reg])
+            .await?;
+        delay.delay_ms(5).await;
+
+        let expected = config_val | (1 << 1);
+        if chip.config_reg != expected {
+            Err(TransferError::CommunicationError(ConfigMismatch {
+                expected,
+                actual: chip.config_reg,
+                setup_aw: chip.read_register(Register::SETUP_AW).await?,
+            }))
+        } else {
+            Ok(chip)
+        }
+    }
+
+    /// Checks if the chip is connected to the SPI bus.
+    ///
+    /// See [`Nrf24l01::is_connected()`](struct.Nrf24l01.html#method.is_connected).
+    pub async fn is_connected(&mut self) -> Result<bool, TransferError<SPI::Error, CE::Error>> {
+        let setup_aw = self.read_register(Register::SETUP_AW).await?;
+        let mut connected = true;
+        for pattern in [0b01, 0b11] {
+            self.write_register(Register::SETUP_AW, &[pattern]).await?;
+            connected &= self.read_register(Register::SETUP_AW).await? == pattern;
+        }
+        self.write_register(Register::SETUP_AW, &[setup_aw]).await?;
+        Ok(connected)
+    }
+
+    /// Returns the SPI device and the Chip Enable pin.
+    pub fn release(self) -> (SPI, CE) {
+        (self.spi, self.ce)
+    }
+
+    /// Opens a reading pipe for reading data on an address.
+    ///
+    /// Returns [`TransferError::InvalidAddressLength`] if the length of `addr` doesn't match the
+    /// configured address width. Data pipes 2 to 5 also accept a single byte, see
+    /// [`Nrf24l01::open_reading_pipe()`](struct.Nrf24l01.html#method.open_reading_pipe).
+    pub async fn open_reading_pipe<T: Into<DataPipe>>(
+        &mut self,
+        pipe: T,
+        addr: &[u8],
+    ) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        let pipe = pipe.into();
+        match pipe {
+            DataPipe::DP0 | DataPipe::DP1 => self.check_address_length(addr)?,
+            _ if addr.len() == 1 => {}
+            _ => self.check_address_length(addr)?,
+        }
+        let rx_address_reg: Register = pipe.into();
+        match pipe {
+            DataPipe::DP0 | DataPipe::DP1 => self.write_register(rx_address_reg, addr).await?,
+            _ => self.write_register(rx_address_reg, &addr[..1]).await?,
+        }
+        let old_reg = self.read_register(Register::EN_RXADDR).await?;
+        self.write_register(Register::EN_RXADDR, &[old_reg | (1 << pipe.pipe())])
+            .await
+    }
+
+    /// Opens a writing pipe for writing data to an address.
+    ///
+    /// Returns [`TransferError::InvalidAddressLength`] if the length of `addr` doesn't match the
+    /// configured address width.
+    pub async fn open_writing_pipe(
+        &mut self,
+        addr: &[u8],
+    ) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        self.check_address_length(addr)?;
+        // Acknowledgements are received on data pipe 0
+        self.write_register(Register::RX_ADDR_P0, addr).await?;
+        self.write_register(Register::TX_ADDR, addr).await
+    }
+
+    /// Starts listening on the pipes that are opened for reading.
+    pub async fn start_listening(&mut self) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        self.config_reg |= 1;
+        self.write_register(Register::CONFIG, &[self.config_reg])
+            .await?;
+        self.reset_status().await?;
+        self.set_ce_high()
+    }
+
+    /// Stops listening.
+    pub async fn stop_listening(&mut self) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        self.set_ce_low()?;
+        self.config_reg &= !0b1;
+        self.write_register(Register::CONFIG, &[self.config_reg])
+            .await
+    }
+
+    /// Checks if there is a payload available to be read.
+    pub async fn data_available(&mut self) -> Result<bool, TransferError<SPI::Error, CE::Error>> {
+        Ok(self.status().await?.data_pipe_available().is_some())
+    }
+
+    /// Waits until a payload is available to be read.
+    ///
+    /// `irq` is the IRQ pin of the chip, the task sleeps until the chip pulls it low. Returns
+    /// right away if the RX FIFO already holds a payload. Interrupt flags are cleared before
+    /// waiting, so the pin is only pulled low again by a new event. Enable the data ready
+    /// interrupt on the pin, see [`Nrf24l01::set_interrupts()`](struct.Nrf24l01.html#method.set_interrupts).
+    ///
+    /// Returns [`TransferError::InterruptWaitFailed`] if waiting on the IRQ pin failed.
+    pub async fn wait_for_data<IRQ: Wait>(
+        &mut self,
+        mut irq: IRQ,
+    ) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        while !self.data_available().await? {
+            self.reset_status().await?;
+            // A payload can arrive between the status read and this wait, so wait for the
+            // level instead of an edge that might already have happened
+            irq.wait_for_low()
+                .await
+                .map_err(|_| TransferError::InterruptWaitFailed)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the next available payload into `buf`.
+    ///
+    /// Returns the number of bytes read. Returns [`TransferError::BufferTooSmall`] if the
+    /// payload doesn't fit in `buf`, the payload is left in the RX FIFO. With dynamic payloads,
+    /// a corrupt width flushes the RX FIFO and returns [`TransferError::CorruptPayload`].
+    pub async fn read(
+        &mut self,
+        buf: &mut [u8],
+    ) -> Result<usize, TransferError<SPI::Error, CE::Error>> {
+        let len = match self.payload_size {
+            PayloadSize::Static(n) => n as usize,
+            PayloadSize::Dynamic => {
+                let width = self.command(Instruction::RRXPLWID, 1).await?[1];
+                if width > MAX_PAYLOAD_SIZE {
+                    self.flush_rx().await?;
+                    return Err(TransferError::CorruptPayload);
+                }
+                width as usize
+            }
+        };
+        if len > buf.len() {
+            return Err(TransferError::BufferTooSmall { len: len as u8 });
+        }
+        let r = self.command(Instruction::RRX, len).await?;
+        buf[..len].copy_from_slice(&r[1..=len]);
+        Ok(len)
+    }
+
+    /// Writes a payload to the opened writing pipe.
+    ///
+    /// Pulses CE to start the transmission and clears the interrupt flags.
+    /// Returns [`TransferError::MaximumRetries`] when the maximum number of retries was reached
+    /// by the previous payload, like [`Nrf24l01::write()`](struct.Nrf24l01.html#method.write).
+    /// Returns [`TransferError::BufferTooLarge`] if `buf` doesn't fit in a single payload,
+    /// [`TransferError::TxFifoFull`] if the payload was dropped because the transmission queue
+    /// was full, and [`TransferError::NotInTxMode`] if the chip is listening.
+    pub async fn write<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        buf: &[u8],
+    ) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        if self.config_reg & 1 != 0 {
+            return Err(TransferError::NotInTxMode);
+        }
+        let (max, send_count) = match self.payload_size {
+            // Static payloads are padded with zeroes
+            PayloadSize::Static(n) => (n, n as usize),
+            PayloadSize::Dynamic => (MAX_PAYLOAD_SIZE, buf.len()),
+        };
+        if buf.len() > max as usize {
+            return Err(TransferError::BufferTooLarge { max });
+        }
+        self.tx_buf[0] = Instruction::WTX.opcode();
+        self.tx_buf[1..=buf.len()].copy_from_slice(buf);
+        self.tx_buf[buf.len() + 1..=send_count].fill(0);
+        self.spi
+            .transfer_in_place(&mut self.tx_buf[..=send_count])
+            .await
+            .map_err(TransferError::Spi)?;
+        let status = Status::from(self.tx_buf[0]);
+        // The payload is dropped when the TX FIFO was full
+        if status.tx_full() {
+            return Err(TransferError::TxFifoFull);
+        }
+
+        // Start the transmission
+        self.set_ce_high()?;
+        delay.delay_us(self.ce_pulse_us as u32).await;
+        self.set_ce_low()?;
+
+        self.reset_status().await?;
+        if status.reached_max_retries() {
+            self.flush_tx().await?;
+            return Err(TransferError::MaximumRetries);
+        }
+        Ok(())
+    }
+
+    /// Reads the status register from device. See [`Status`](status/struct.Status.html).
+    ///
+    /// Returns [`TransferError::InvalidStatus`] if the status is not valid.
+    pub async fn status(&mut self) -> Result<Status, TransferError<SPI::Error, CE::Error>> {
+        let status = Status::from(self.command(Instruction::NOP, 0).await?[0]);
+        if !status.is_valid() {
+            return Err(TransferError::InvalidStatus);
+        }
+        Ok(status)
+    }
+
+    /// Resets the data ready, data sent and maximum retries flags in the status register.
+    pub async fn reset_status(&mut self) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        self.write_register(Register::STATUS, &[Self::STATUS_RESET])
+            .await
+    }
+
+    /// Flushes the transmission FIFO.
+    pub async fn flush_tx(&mut self) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        self.command(Instruction::FTX, 0).await.map(|_| ())
+    }
+
+    /// Flushes the receive FIFO.
+    pub async fn flush_rx(&mut self) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        self.command(Instruction::FRX, 0).await.map(|_| ())
+    }
+
+    /// Sets the payload size, see
+    /// [`Nrf24l01::set_payload_size()`](struct.Nrf24l01.html#method.set_payload_size).
+    pub async fn set_payload_size<T: Into<PayloadSize>>(
+        &mut self,
+        payload_size: T,
+    ) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        let payload_size = payload_size.into().truncate();
+        match payload_size {
+            PayloadSize::Static(n) => {
+                if self.payload_size == PayloadSize::Dynamic {
+                    // Disable dynamic payloads, and ack payloads which require them
+                    let features = self.features().await?;
+                    self.set_features(features.dynamic_payloads(false).ack_payloads(false))
+                        .await?;
+                    self.write_register(Register::DYNPD, &[0]).await?;
+                }
+                for register in [
+                    Register::RX_PW_P0,
+                    Register::RX_PW_P1,
+                    Register::RX_PW_P2,
+                    Register::RX_PW_P3,
+                    Register::RX_PW_P4,
+                    Register::RX_PW_P5,
+                ] {
+                    self.write_register(register, &[n]).await?;
+                }
+            }
+            PayloadSize::Dynamic => {
+                let features = self.features().await?;
+                self.set_features(features.dynamic_payloads(true)).await?;
+                // Enable on all pipes
+                self.write_register(Register::DYNPD, &[0b0011_1111]).await?;
+            }
+        }
+        self.payload_size = payload_size;
+        Ok(())
+    }
+
+    /// Returns the payload size.
+    pub fn payload_size(&self) -> PayloadSize {
+        self.payload_size
+    }
+
+    /// Sets the optional features of the chip in the FEATURE register. See [`Features`].
+    pub async fn set_features(
+        &mut self,
+        features: Features,
+    ) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        self.write_register(Register::FEATURE, &[features.value()])
+            .await
+    }
+
+    /// Returns the optional features of the chip, read from the FEATURE register.
+    pub async fn features(&mut self) -> Result<Features, TransferError<SPI::Error, CE::Error>> {
+        self.read_register(Register::FEATURE)
+            .await
+            .map(Features::from)
+    }
+
+    /// Checks that the length of an address matches the configured address width.
+    fn check_address_length(
+        &self,
+        addr: &[u8],
+    ) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        let width = self.addr_width.bytes();
+        if addr.len() != width as usize {
+            return Err(TransferError::InvalidAddressLength {
+                expected: width,
+                got: addr.len() as u8,
+            });
+        }
+        Ok(())
+    }
+
+    /// Sends an instruction followed by `len` bytes of `tx_buf`, and returns the response,
+    /// starting with the status.
+    async fn command(
+        &mut self,
+        instruction: Instruction,
+        len: usize,
+    ) -> Result<&[u8], TransferError<SPI::Error, CE::Error>> {
+        self.tx_buf[0] = instruction.opcode();
+        self.tx_buf[1..=len].fill(0);
+        self.spi
+            .transfer_in_place(&mut self.tx_buf[..=len])
+            .await
+            .map_err(TransferError::Spi)?;
+        Ok(&self.tx_buf[..=len])
+    }
+
+    async fn write_register(
+        &mut self,
+        register: Register,
+        buf: &[u8],
+    ) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        self.tx_buf[0] = Instruction::WR.opcode() | register.addr();
+        self.tx_buf[1..=buf.len()].copy_from_slice(buf);
+        self.spi
+            .write(&self.tx_buf[..=buf.len()])
+            .await
+            .map_err(TransferError::Spi)
+    }
+
+    async fn read_register(
+        &mut self,
+        register: Register,
+    ) -> Result<u8, TransferError<SPI::Error, CE::Error>> {
+        let mut buf = [Instruction::RR.opcode() | register.addr(), 0];
+        self.spi
+            .transfer_in_place(&mut buf)
+            .await
+            .map_err(TransferError::Spi)?;
+        Ok(buf[1])
+    }
+
+    fn from_parts(spi: SPI, ce: CE, config: &NrfConfig) -> Self {
+        Nrf24l01Async {
+            spi,
+            ce,
+            config_reg: 0,
+            addr_width: config.addr_width,
+            payload_size: PayloadSize::Static(0),
+            tx_buf: [0; MAX_PAYLOAD_SIZE as usize + 1],
+            ce_pulse_us: config.ce_pulse_us,
+        }
+    }
+
+    fn set_ce_high(&mut self) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        self.ce.set_high().map_err(TransferError::Pin)
+    }
+
+    fn set_ce_low(&mut self) -> Result<(), TransferError<SPI::Error, CE::Error>> {
+        self.ce.set_low().map_err(TransferError::Pin)
+    }
+}
+
+impl<SPI, CE> fmt::Debug for Nrf24l01Async<SPI, CE>
+where
+    SPI: fmt::Debug,
+    CE: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Nrf24l01Async")
+            .field("spi", &self.spi)
+            .field("ce", &self.ce)
+            .field("config_reg", &self.config_reg)
+            .field("payload_size", &self.payload_size)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{block_on, DelayMock, PinMock, SpiMock, Transaction};
+
+    // Status value of an idle chip: RX FIFO empty
+    const STATUS: u8 = 0b0000_1110;
+
+    type Chip = Nrf24l01Async<SpiMock, PinMock>;
+
+    fn chip(expectations: &[Transaction], payload_size: PayloadSize) -> (Chip, SpiMock) {
+        let spi = SpiMock::new(expectations);
+        let mut chip =
+            Nrf24l01Async::from_parts(spi.clone(), PinMock::default(), &NrfConfig::default());
+        chip.payload_size = payload_size;
+        (chip, spi)
+    }
+
+    #[test]
+    fn new_configures_chip() {
+        let spi = SpiMock::new(&[
+            // Connection check
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b11]),
+            Transaction::write(&[0x23, 0b01]),
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b01]),
+            Transaction::write(&[0x23, 0b11]),
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b11]),
+            Transaction::write(&[0x23, 0b11]),
+            Transaction::write(&[0x24, 0x5f]),
+            Transaction::write(&[0x26, 0x00]),
+            Transaction::write(&[0x31, 32]),
+            Transaction::write(&[0x32, 32]),
+            Transaction::write(&[0x33, 32]),
+            Transaction::write(&[0x34, 32]),
+            Transaction::write(&[0x35, 32]),
+            Transaction::write(&[0x36, 32]),
+            Transaction::transfer(&[0x1d, 0], &[STATUS, 0]),
+            Transaction::write(&[0x3d, 0]),
+            Transaction::write(&[0x23, 0b11]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::write(&[0x25, 76]),
+            Transaction::transfer(&[0xe2], &[STATUS]),
+            Transaction::transfer(&[0xe1], &[STATUS]),
+            Transaction::write(&[0x20, 0b0000_1100]),
+            Transaction::transfer(&[0x00, 0], &[STATUS, 0b0000_1100]),
+            Transaction::write(&[0x20, 0b0000_1110]),
+        ]);
+        let chip = block_on(Nrf24l01Async::new(
+            spi.clone(),
+            PinMock::default(),
+            &mut DelayMock,
+            NrfConfig::default(),
+        ));
+        assert!(chip.is_ok());
+        spi.done();
+    }
+
+    #[test]
+    fn wait_for_data_returns_when_data_is_available() {
+        let (mut chip, spi) = chip(
+            &[Transaction::transfer(&[0xff], &[0b0100_0010])],
+            PayloadSize::Static(32),
+        );
+        let mut irq = PinMock::default();
+        irq.set_high().unwrap();
+        assert!(block_on(chip.wait_for_data(&mut irq)).is_ok());
+        // The IRQ pin was not waited on
+        assert!(irq.is_high());
+        spi.done();
+    }
+
+    #[test]
+    fn wait_for_data_clears_flags_and_waits_on_irq() {
+        let (mut chip, spi) = chip(
+            &[
+                Transaction::transfer(&[0xff], &[STATUS]),
+                Transaction::write(&[0x27, 0b0111_0000]),
+                Transaction::transfer(&[0xff], &[0b0100_0010]),
+            ],
+            PayloadSize::Static(32),
+        );
+        let mut irq = PinMock::default();
+        irq.set_high().unwrap();
+        assert!(block_on(chip.wait_for_data(&mut irq)).is_ok());
+        assert!(!irq.is_high());
+        spi.done();
+    }
+
+    #[test]
+    fn read_dynamic_payload() {
+        let (mut chip, spi) = chip(
+            &[
+                Transaction::transfer(&[0x60, 0], &[STATUS, 3]),
+                Transaction::transfer(&[0x61, 0, 0, 0], &[STATUS, 1, 2, 3]),
+            ],
+            PayloadSize::Dynamic,
+        );
+        let mut buf = [0; 32];
+        assert_eq!(block_on(chip.read(&mut buf)).unwrap(), 3);
+        assert_eq!(&buf[..3], &[1, 2, 3]);
+        spi.done();
+    }
+
+    #[test]
+    fn read_flushes_rx_on_corrupt_width() {
+        let (mut chip, spi) = chip(
+            &[
+                Transaction::transfer(&[0x60, 0], &[STATUS, 33]),
+                Transaction::transfer(&[0xe2], &[STATUS]),
+            ],
+            PayloadSize::Dynamic,
+        );
+        let mut buf = [0; 32];
+        assert!(matches!(
+            block_on(chip.read(&mut buf)),
+            Err(TransferError::CorruptPayload)
+        ));
+        spi.done();
+    }
+
+    #[test]
+    fn read_rejects_small_buffer() {
+        let (mut chip, spi) = chip(&[], PayloadSize::Static(4));
+        let mut buf = [0; 2];
+        assert!(matches!(
+            block_on(chip.read(&mut buf)),
+            Err(TransferError::BufferTooSmall { len: 4 })
+        ));
+        spi.done();
+    }
+
+    #[test]
+    fn write_pads_static_payload() {
+        let (mut chip, spi) = chip(
+            &[
+                Transaction::transfer(&[0xa0, 1, 2, 0, 0], &[STATUS, 0, 0, 0, 0]),
+                Transaction::write(&[0x27, 0b0111_0000]),
+            ],
+            PayloadSize::Static(4),
+        );
+        assert!(block_on(chip.write(&mut DelayMock, &[1, 2])).is_ok());
+        assert!(!chip.ce.is_high());
+        spi.done();
+    }
+
+    #[test]
+    fn write_rejects_large_buffer() {
+        let (mut chip, spi) = chip(&[], PayloadSize::Static(4));
+        assert!(matches!(
+            block_on(chip.write(&mut DelayMock, &[0; 5])),
+            Err(TransferError::BufferTooLarge { max: 4 })
+        ));
+        spi.done();
+    }
+}
@@ -0,0 +1,105 @@
+//! Channel presets that avoid interference with WiFi.
+//!
+//! WiFi channel `n` in 1..=13 is centered on `2407 + 5n` MHz, and channel 14 on 2484 MHz. Each
+//! WiFi channel occupies about 22 MHz. Most networks use the non-overlapping channels 1, 6 and
+//! 11, which leaves small gaps between them and some room above channel 11.
+//!
+//! The default channel 76 (2476 MHz) overlaps WiFi channels 12 and 13, and lies just above
+//! channel 11.
+//!
+//! Every channel inside the 2400..=2483 MHz ISM band overlaps some WiFi channel. Channels above
+//! 2496 MHz are clear of all WiFi channels, but lie outside the band most regions allow, so
+//! none of the presets use them.
+//!
+//! # Examples
+//! ```rust
+//! use nrf24_rs::channels;
+//! use nrf24_rs::config::NrfConfig;
+//!
+//! // Stay clear of a busy access point on WiFi channel 6
+//! let channel = channels::avoiding_wifi(6).unwrap();
+//! let config = NrfConfig::default().channel(channel);
+//! ```
+use crate::config::BASE_FREQUENCY;
+use crate::error::ConfigError;
+
+/// Channel in the gap between WiFi channels 1 and 6, at 2425 MHz.
+pub const BETWEEN_WIFI_1_AND_6: u8 = 25;
+/// Channel in the gap between WiFi channels 6 and 11, at 2450 MHz.
+pub const BETWEEN_WIFI_6_AND_11: u8 = 50;
+/// Channel above WiFi channel 11, at 2480 MHz. Still overlaps WiFi channel 13, which is used
+/// outside of the Americas.
+pub const ABOVE_WIFI_11: u8 = 80;
+
+/// All presets, from low to high.
+pub const PRESETS: [u8; 3] = [BETWEEN_WIFI_1_AND_6, BETWEEN_WIFI_6_AND_11, ABOVE_WIFI_11];
+
+// Half the width of a WiFi channel, plus the 1 MHz half width of a channel at 2 Mbps.
+const WIFI_HALF_WIDTH_MHZ: u16 = 11 + 1;
+
+/// Returns a channel that avoids the common WiFi channels 1, 6 and 11.
+///
+/// The channel lies at 2480 MHz, so it still overlaps WiFi channels 12 and 13, which are used
+/// outside of the Americas. To stay clear of a known WiFi channel, use [`avoiding_wifi()`].
+///
+/// # Examples
+/// ```rust
+/// use nrf24_rs::channels::{wifi_safe, ABOVE_WIFI_11};
+///
+/// assert_eq!(wifi_safe(), ABOVE_WIFI_11);
+/// ```
+pub fn wifi_safe() -> u8 {
+    ABOVE_WIFI_11
+}
+
+/// Returns the center frequency in MHz of a WiFi channel.
+///
+/// Returns [`ConfigError::InvalidWifiChannel`] if the channel is not in 1..=14.
+///
+/// # Examples
+/// ```rust
+/// use nrf24_rs::channels::wifi_frequency;
+///
+/// assert_eq!(wifi_frequency(6), Ok(2437));
+/// assert!(wifi_frequency(15).is_err());
+/// ```
+pub fn wifi_frequency(wifi_channel: u8) -> Result<u16, ConfigError> {
+    match wifi_channel {
+        1..=13 => Ok(2407 + 5 * wifi_channel as u16),
+        14 => Ok(2484),
+        _ => Err(ConfigError::InvalidWifiChannel(wifi_channel)),
+    }
+}
+
+/// Returns the preset that lies furthest from a WiFi channel.
+///
+/// The returned channel never overlaps the WiFi channel, not even at 2 Mbps. See [`PRESETS`].
+///
+/// Returns [`ConfigError::InvalidWifiChannel`] if the channel is not in 1..=14.
+///
+/// # Examples
+/// ```rust
+/// use nrf24_rs::channels::{avoiding_wifi, ABOVE_WIFI_11, BETWEEN_WIFI_1_AND_6};
+///
+/// assert_eq!(avoiding_wifi(1), Ok(ABOVE_WIFI_11));
+/// assert_eq!(avoiding_wifi(13), Ok(BETWEEN_WIFI_1_AND_6));
+/// ```
+pub fn avoiding_wifi(wifi_channel: u8) -> Result<u8, ConfigError> {
+    let wifi = wifi_frequency(wifi_channel)?;
+    let distance = |channel: &u8| {
+        let frequency = BASE_FREQUENCY + *channel as u16;
+        if frequency > wifi {
+            frequency - wifi
+        } else {
+            wifi - frequency
+        }
+    };
+    let channel = PRESETS
+        .iter()
+        .copied()
+        .max_by_key(distance)
+        .unwrap_or(ABOVE_WIFI_11);
+    // The presets are far enough apart that one of them is always clear
+    debug_assert!(distance(&channel) > WIFI_HALF_WIDTH_MHZ);
+    Ok(channel)
+}
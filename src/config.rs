@@ -8,18 +8,54 @@
 //! * `addr_width`:             address width of 5 bytes.
 //! * `ack_payloads_enabled`:   false: acknowledgement payloads are disabled by default.
 //! * `auto_retry`:             enabled, will wait 1586µs on ack, and will retry 15 times.
+//! * `ce_pulse_us`:            10µs, the minimum CE pulse that starts a transmission.
 //! * `channel`:                channel 76.
 //! * `crc_encoding_scheme`:    encoding scheme with 2 bytes.
 //! * `data_rate`:              1Mbps.
+//! * `dynamic_ack`:            false: every payload requests an acknowledgement.
+//! * `masked_interrupts`:      none: all interrupts are reflected on the IRQ pin.
 //! * `payload_size`:           static payload size of [`MAX_PAYLOAD_SIZE`] bytes.
 //! * `pa_level`:               min amplification level.
+//! * `spi_retries`:            0: failed SPI transactions are not retried.
 //!
+use crate::error::ConfigError;
 use crate::register_acces::Register;
+use crate::status::Interrupts;
 use crate::MAX_PAYLOAD_SIZE;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 #[cfg(feature = "micro-fmt")]
 use ufmt::{uDebug, uWrite, uwrite, Formatter};
 
-const MAX_CHANNEL: u8 = 125;
+pub(crate) const MAX_CHANNEL: u8 = 125;
+pub(crate) const MIN_CE_PULSE_US: u8 = 10;
+pub(crate) const BASE_FREQUENCY: u16 = 2400;
+
+/// Returns the RF channel for a frequency in MHz.
+///
+/// The nRF24L01 operates on `2400 + channel` MHz, for channels in [0..125].
+/// Frequencies outside of 2400..=2525 MHz return a [`ConfigError`].
+///
+/// This is a pure function, so it can be used to validate a frequency plan before
+/// touching any hardware.
+///
+/// # Notes
+/// Each channel occupies less than 1 MHz at 250 kbps and 1 Mbps, but 2 MHz at 2 Mbps.
+/// Channels at 2 Mbps should therefore be spaced at least 2 MHz apart to avoid overlap.
+///
+/// # Examples
+/// ```rust
+/// use nrf24_rs::config::channel_for_frequency;
+///
+/// assert_eq!(channel_for_frequency(2476), Ok(76));
+/// assert!(channel_for_frequency(2530).is_err());
+/// ```
+pub fn channel_for_frequency(mhz: u16) -> Result<u8, ConfigError> {
+    match mhz.checked_sub(BASE_FREQUENCY) {
+        Some(channel) if channel <= MAX_CHANNEL as u16 => Ok(channel as u8),
+        _ => Err(ConfigError::FrequencyOutOfRange(mhz)),
+    }
+}
 
 /// Configuration builder struct for NRF chip.
 ///
@@ -27,33 +63,46 @@ const MAX_CHANNEL: u8 = 125;
 /// the builder pattern.
 ///
 /// # Example: default
-/// ```rust
-/// use nrf24::Nrf24l01;
-/// use nrf24::config::NrfConfig;
+/// ```no_run
+/// # #![cfg_attr(not(feature = "test-util"), no_main, cfg(any()))]
+/// # use nrf24_rs::doctest::*;
+/// # let (spi, ce, ncs, mut delay) = peripherals();
+/// use nrf24_rs::Nrf24l01;
+/// use nrf24_rs::config::NrfConfig;
 ///
 /// let config = NrfConfig::default();
 ///
-/// let mut chip = Nrf24l01::new(spi, ce, ncs, delay, config)?;
+/// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config)?;
+/// # Ok::<(), Error>(())
 /// ```
 ///
 /// # Example: custom configuration
-/// ```rust
-/// use nrf24::Nrf24l01;
-/// use nrf24::config::{PALevel, DataRate, NrfConfig, PayloadSize};
+/// ```no_run
+/// # #![cfg_attr(not(feature = "test-util"), no_main, cfg(any()))]
+/// # use nrf24_rs::doctest::*;
+/// # let (spi, ce, ncs, mut delay) = peripherals();
+/// use nrf24_rs::Nrf24l01;
+/// use nrf24_rs::config::{PALevel, DataRate, NrfConfig, PayloadSize};
 ///
 /// let config = NrfConfig::default()
 ///     .payload_size(PayloadSize::Dynamic) // set dynamic payload size
 ///     .channel(7)
-///     .addr_width(3),
+///     .addr_width(3)
 ///     .data_rate(DataRate::R2Mbps)
 ///     .pa_level(PALevel::Max)
 ///     .crc_encoding_scheme(None) // disable crc
 ///     .ack_payloads_enabled(true)
 ///     .auto_retry((15, 15));
 ///
-/// let mut chip = Nrf24l01::new(spi, ce, ncs, delay, config)?;
+/// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config)?;
+/// # Ok::<(), Error>(())
 /// ```
 #[derive(Copy, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(from = "NrfConfigFields")
+)]
 pub struct NrfConfig {
     pub(crate) payload_size: PayloadSize,
     pub(crate) channel: u8,
@@ -62,7 +111,11 @@ pub struct NrfConfig {
     pub(crate) pa_level: PALevel,
     pub(crate) crc_encoding_scheme: Option<EncodingScheme>,
     pub(crate) ack_payloads_enabled: bool,
+    pub(crate) dynamic_ack: bool,
+    pub(crate) masked_interrupts: Interrupts,
     pub(crate) auto_retry: AutoRetransmission,
+    pub(crate) spi_retries: u8,
+    pub(crate) ce_pulse_us: u8,
 }
 
 impl NrfConfig {
@@ -75,6 +128,8 @@ impl NrfConfig {
     }
     /// Set RF channel
     /// Must be a number in [0..125], values outside will be clipped
+    ///
+    /// See [`channels`](crate::channels) for channels that avoid WiFi.
     pub fn channel(mut self, channel: u8) -> Self {
         self.channel = core::cmp::min(channel, MAX_CHANNEL);
         self
@@ -101,16 +156,83 @@ impl NrfConfig {
         self.crc_encoding_scheme = crc_encoding_scheme;
         self
     }
-    /// Configure if auto acknowledgements are enabled
+    /// Configure if acknowledgement payloads are enabled
+    /// Acknowledgement payloads require dynamic payloads, so enabling them overrides the
+    /// payload size with [`PayloadSize::Dynamic`].
     pub fn ack_payloads_enabled(mut self, ack_payloads_enabled: bool) -> Self {
         self.ack_payloads_enabled = ack_payloads_enabled;
         self
     }
+    /// Configure if individual payloads can be sent without requesting an acknowledgement.
+    /// Required for [`write_no_ack()`](../struct.Nrf24l01.html#method.write_no_ack).
+    pub fn dynamic_ack(mut self, dynamic_ack: bool) -> Self {
+        self.dynamic_ack = dynamic_ack;
+        self
+    }
+    /// Set the interrupts that are masked, and will not be reflected on the IRQ pin
+    /// Applied with the first write to the CONFIG register, so masked interrupts never
+    /// trigger the IRQ pin during initialization.
+    pub fn masked_interrupts(mut self, masked_interrupts: Interrupts) -> Self {
+        self.masked_interrupts = masked_interrupts;
+        self
+    }
     /// Set the automatic retransmission config
     pub fn auto_retry<T: Into<AutoRetransmission>>(mut self, auto_retry: T) -> Self {
         self.auto_retry = auto_retry.into();
         self
     }
+    /// Set the number of times a failed SPI transaction is retried before returning an error.
+    /// Useful on noisy or heavily shared buses.
+    pub fn spi_retries(mut self, spi_retries: u8) -> Self {
+        self.spi_retries = spi_retries;
+        self
+    }
+    /// Set the time in µs that CE is held high to start a transmission.
+    /// Values below the 10µs minimum of the datasheet will be clipped. A larger value adds a
+    /// safety margin on platforms where the microsecond delay is coarse or optimistic.
+    pub fn ce_pulse_us(mut self, ce_pulse_us: u8) -> Self {
+        self.ce_pulse_us = core::cmp::max(ce_pulse_us, MIN_CE_PULSE_US);
+        self
+    }
+}
+
+/// Deserialized fields of [`NrfConfig`], which are passed through the builder methods so the
+/// values are clipped in the same way.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(rename = "NrfConfig")]
+struct NrfConfigFields {
+    payload_size: PayloadSize,
+    channel: u8,
+    addr_width: AddressWidth,
+    data_rate: DataRate,
+    pa_level: PALevel,
+    crc_encoding_scheme: Option<EncodingScheme>,
+    ack_payloads_enabled: bool,
+    dynamic_ack: bool,
+    masked_interrupts: Interrupts,
+    auto_retry: AutoRetransmission,
+    spi_retries: u8,
+    ce_pulse_us: u8,
+}
+
+#[cfg(feature = "serde")]
+impl From<NrfConfigFields> for NrfConfig {
+    fn from(fields: NrfConfigFields) -> Self {
+        NrfConfig::default()
+            .payload_size(fields.payload_size)
+            .channel(fields.channel)
+            .addr_width(fields.addr_width)
+            .data_rate(fields.data_rate)
+            .pa_level(fields.pa_level)
+            .crc_encoding_scheme(fields.crc_encoding_scheme)
+            .ack_payloads_enabled(fields.ack_payloads_enabled)
+            .dynamic_ack(fields.dynamic_ack)
+            .masked_interrupts(fields.masked_interrupts)
+            .auto_retry(fields.auto_retry)
+            .spi_retries(fields.spi_retries)
+            .ce_pulse_us(fields.ce_pulse_us)
+    }
 }
 
 impl Default for NrfConfig {
@@ -123,7 +245,11 @@ impl Default for NrfConfig {
             pa_level: PALevel::default(),
             data_rate: DataRate::default(),
             ack_payloads_enabled: false,
+            dynamic_ack: false,
+            masked_interrupts: Interrupts::new(),
             auto_retry: AutoRetransmission::default(),
+            spi_retries: 0,
+            ce_pulse_us: MIN_CE_PULSE_US,
         }
     }
 }
@@ -144,8 +270,12 @@ impl uDebug for NrfConfig {
                 "acknowledgement payloads enabled",
                 &self.ack_payloads_enabled,
             )?
+            .field("dynamic acknowledgements", &self.dynamic_ack)?
+            .field("masked interrupts", &self.masked_interrupts)?
             .field("address width", &self.addr_width)?
             .field("crc encoding scheme", &self.crc_encoding_scheme)?
+            .field("spi retries", &self.spi_retries)?
+            .field("CE pulse", &self.ce_pulse_us)?
             .finish()
     }
 }
@@ -155,6 +285,7 @@ impl uDebug for NrfConfig {
 ///
 /// Defaults to Min.
 #[derive(PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PALevel {
     /// -18 dBm, 7 mA current consumption.
     Min = 0b0000_0000,
@@ -187,8 +318,7 @@ impl From<u8> for PALevel {
             0b0000_0000 => Self::Min,
             0b0000_0010 => Self::Low,
             0b0000_0100 => Self::High,
-            0b0000_0110 => Self::Max,
-            _ => unreachable!(),
+            _ => Self::Max,
         }
     }
 }
@@ -220,6 +350,7 @@ impl uDebug for PALevel {
 
 /// Enum representing the payload size.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PayloadSize {
     /// The chip will dynamically set the payload size, depending on the message size.
     Dynamic,
@@ -267,18 +398,22 @@ impl uDebug for PayloadSize {
 
 /// Configured speed at which data will be sent.
 ///
-/// Defaults to 2Mpbs.
+/// Defaults to 1Mpbs.
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DataRate {
     /// 1 Mbps
     R1Mbps = 0b0000_0000,
     /// 2 Mbps
-    R2Mbps = 0b0000_0001,
+    R2Mbps = 0b0000_1000,
+    /// 250 kbps, only supported by the nRF24L01+.
+    /// Trades bandwidth for range.
+    R250kbps = 0b0010_0000,
 }
 
 impl DataRate {
     pub(crate) fn bitmask() -> u8 {
-        0b0000_1000
+        0b0010_1000
     }
     pub(crate) fn rate(&self) -> u8 {
         *self as u8
@@ -296,7 +431,8 @@ impl From<u8> for DataRate {
         match t & Self::bitmask() {
             0b0000_0000 => Self::R1Mbps,
             0b0000_1000 => Self::R2Mbps,
-            _ => unreachable!(),
+            // Setting both RF_DR_LOW and RF_DR_HIGH is reserved, reads of the register reject it
+            _ => Self::R250kbps,
         }
     }
 }
@@ -310,12 +446,36 @@ impl uDebug for DataRate {
         match *self {
             DataRate::R1Mbps => f.write_str("1 Mbps"),
             DataRate::R2Mbps => f.write_str("2 Mbps"),
+            DataRate::R250kbps => f.write_str("250 kbps"),
+        }
+    }
+}
+
+/// Variant of the chip, see [`chip_variant()`](../struct.Nrf24l01.html#method.chip_variant).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ChipVariant {
+    /// nRF24L01+, supports the 250 kbps data rate and the received power detector.
+    Plus,
+    /// The original nRF24L01.
+    Legacy,
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for ChipVariant {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        match *self {
+            ChipVariant::Plus => f.write_str("nRF24L01+"),
+            ChipVariant::Legacy => f.write_str("nRF24L01"),
         }
     }
 }
 
 /// Cyclic Redundancy Check encoding scheme.
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum EncodingScheme {
     /// 1 byte
     R1Byte = 0,
@@ -337,8 +497,7 @@ impl From<u8> for EncodingScheme {
     fn from(t: u8) -> Self {
         match t & Self::bitmask() {
             0b0000_0000 => Self::R1Byte,
-            0b0000_0100 => Self::R2Bytes,
-            _ => unreachable!(),
+            _ => Self::R2Bytes,
         }
     }
 }
@@ -356,8 +515,92 @@ impl uDebug for EncodingScheme {
     }
 }
 
+/// Optional features of the chip, as configured in the FEATURE register.
+///
+/// Constructed through the builder pattern, all features are disabled by default.
+///
+/// # Examples
+/// ```rust
+/// use nrf24_rs::config::Features;
+///
+/// let features = Features::new().dynamic_payloads(true).ack_payloads(true);
+/// assert!(features.ack_payloads_enabled());
+/// assert!(!features.dynamic_ack_enabled());
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Features(u8);
+
+impl Features {
+    const EN_DYN_ACK: u8 = 1 << 0;
+    const EN_ACK_PAY: u8 = 1 << 1;
+    const EN_DPL: u8 = 1 << 2;
+
+    /// Creates a set of features with all features disabled.
+    pub fn new() -> Self {
+        Self(0)
+    }
+    /// Configure if dynamic payload lengths are enabled (`EN_DPL`).
+    /// Dynamic payloads also have to be enabled per data pipe.
+    pub fn dynamic_payloads(self, enabled: bool) -> Self {
+        self.with(Self::EN_DPL, enabled)
+    }
+    /// Configure if acknowledgement payloads are enabled (`EN_ACK_PAY`).
+    /// Acknowledgement payloads require dynamic payloads.
+    pub fn ack_payloads(self, enabled: bool) -> Self {
+        self.with(Self::EN_ACK_PAY, enabled)
+    }
+    /// Configure if payloads can be sent without requesting an acknowledgement (`EN_DYN_ACK`).
+    pub fn dynamic_ack(self, enabled: bool) -> Self {
+        self.with(Self::EN_DYN_ACK, enabled)
+    }
+    /// Returns `true` if dynamic payload lengths are enabled.
+    pub fn dynamic_payloads_enabled(&self) -> bool {
+        self.0 & Self::EN_DPL != 0
+    }
+    /// Returns `true` if acknowledgement payloads are enabled.
+    pub fn ack_payloads_enabled(&self) -> bool {
+        self.0 & Self::EN_ACK_PAY != 0
+    }
+    /// Returns `true` if payloads can be sent without requesting an acknowledgement.
+    pub fn dynamic_ack_enabled(&self) -> bool {
+        self.0 & Self::EN_DYN_ACK != 0
+    }
+    pub(crate) fn value(&self) -> u8 {
+        self.0
+    }
+    fn with(mut self, bit: u8, enabled: bool) -> Self {
+        if enabled {
+            self.0 |= bit;
+        } else {
+            self.0 &= !bit;
+        }
+        self
+    }
+}
+
+impl From<u8> for Features {
+    fn from(t: u8) -> Self {
+        Self(t & (Self::EN_DYN_ACK | Self::EN_ACK_PAY | Self::EN_DPL))
+    }
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for Features {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        f.debug_struct("Features")?
+            .field("dynamic payloads", &self.dynamic_payloads_enabled())?
+            .field("ack payloads", &self.ack_payloads_enabled())?
+            .field("dynamic ack", &self.dynamic_ack_enabled())?
+            .finish()
+    }
+}
+
 /// Address width for the reading and writing pipes.
 #[derive(PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AddressWidth {
     /// 3 bytes
     R3Bytes = 1,
@@ -371,12 +614,18 @@ impl AddressWidth {
     pub(crate) fn value(&self) -> u8 {
         *self as u8
     }
-    pub(crate) fn from_register(t: u8) -> Self {
-        match t & 0b11 {
-            0b01 => Self::R3Bytes,
-            0b10 => Self::R4Bytes,
-            0b11 => Self::R5Bytes,
-            _ => unreachable!(),
+    /// Returns the address width in bytes.
+    pub(crate) fn bytes(&self) -> u8 {
+        self.value() + 2
+    }
+    /// Returns `None` for the illegal value 0b00 and for values with reserved bits set, which
+    /// are only read back on a fault.
+    pub(crate) fn from_register(t: u8) -> Option<Self> {
+        match t {
+            0b01 => Some(Self::R3Bytes),
+            0b10 => Some(Self::R4Bytes),
+            0b11 => Some(Self::R5Bytes),
+            _ => None,
         }
     }
 }
@@ -432,11 +681,33 @@ impl uDebug for AddressWidth {
 /// * Auto retransmission delay has a default value of 5, which means `1586 µs`.
 /// * The chip will try to resend a failed message 15 times by default.
 #[derive(PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(from = "AutoRetransmissionFields")
+)]
 pub struct AutoRetransmission {
     delay: u8,
     count: u8,
 }
 
+/// Deserialized fields of [`AutoRetransmission`], clipped to 15 like the `(delay, count)`
+/// conversion.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(rename = "AutoRetransmission")]
+struct AutoRetransmissionFields {
+    delay: u8,
+    count: u8,
+}
+
+#[cfg(feature = "serde")]
+impl From<AutoRetransmissionFields> for AutoRetransmission {
+    fn from(fields: AutoRetransmissionFields) -> Self {
+        Self::from((fields.delay, fields.count))
+    }
+}
+
 impl Default for AutoRetransmission {
     fn default() -> Self {
         Self {
@@ -546,6 +817,17 @@ impl DataPipe {
     pub(crate) fn pipe(&self) -> u8 {
         *self as u8
     }
+    /// Returns the RX_PW register holding the static payload width of this pipe.
+    pub(crate) fn payload_width_register(&self) -> Register {
+        match self {
+            DataPipe::DP0 => Register::RX_PW_P0,
+            DataPipe::DP1 => Register::RX_PW_P1,
+            DataPipe::DP2 => Register::RX_PW_P2,
+            DataPipe::DP3 => Register::RX_PW_P3,
+            DataPipe::DP4 => Register::RX_PW_P4,
+            DataPipe::DP5 => Register::RX_PW_P5,
+        }
+    }
 }
 
 impl Default for DataPipe {
@@ -568,6 +850,7 @@ impl From<u8> for DataPipe {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<Register> for DataPipe {
     fn into(self) -> Register {
         match self {
@@ -598,12 +881,62 @@ impl uDebug for DataPipe {
     }
 }
 
+/// A 5 byte pipe address.
+///
+/// Its debug representation prints the bytes as hexadecimal, which is useful for logging
+/// addresses that aren't valid text.
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub struct Address([u8; 5]);
+
+impl Address {
+    /// Returns the bytes of the address, in the order they are written to the chip.
+    pub fn bytes(&self) -> [u8; 5] {
+        self.0
+    }
+}
+
+impl From<[u8; 5]> for Address {
+    fn from(t: [u8; 5]) -> Self {
+        Address(t)
+    }
+}
+
+impl core::fmt::Debug for Address {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:02x?}", self.0)
+    }
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for Address {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        f.write_str("[")?;
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            let digits = [HEX[(byte >> 4) as usize], HEX[(byte & 0xf) as usize]];
+            // Hex digits are always valid utf8
+            f.write_str(core::str::from_utf8(&digits).unwrap_or("??"))?;
+        }
+        f.write_str("]")
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub(crate) enum Mode {
     TransmissionMode,
     ReceiverMode,
 }
 
+/// Snapshot of the chip configuration, see
+/// [`debug_view()`](../struct.Nrf24l01.html#method.debug_view).
+///
+/// Meant to be printed through its `Debug` implementation.
 #[derive(Copy, Clone)]
 pub struct DebugInfo {
     pub(crate) channel: u8,
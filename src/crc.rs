@@ -0,0 +1,65 @@
+//! Software CRC-16 for payloads.
+//!
+//! Uses the CRC-16 CCITT polynomial `0x1021` with an initial value of `0xffff`, the same as the
+//! 2 byte CRC of the chip. The CRC is stored big endian, after the data it covers.
+//!
+//! Useful when the hardware CRC is disabled, e.g. to talk to transmitters that don't use it,
+//! while still checking the integrity of the payloads. See
+//! [`set_software_crc()`](../struct.Nrf24l01.html#method.set_software_crc) to let the driver
+//! handle it.
+//!
+//! # Examples
+//! ```rust
+//! use nrf24_rs::crc::{append_crc, verify_crc};
+//!
+//! let mut frame = [1, 2, 3, 0, 0];
+//! append_crc(&mut frame);
+//! assert!(verify_crc(&frame));
+//! frame[0] = 0;
+//! assert!(!verify_crc(&frame));
+//! ```
+
+/// Number of bytes taken up by the CRC.
+pub const CRC_LEN: usize = 2;
+
+/// Computes the CRC-16 of `data`.
+///
+/// # Examples
+/// ```rust
+/// assert_eq!(nrf24_rs::crc::crc16(b"123456789"), 0x29b1);
+/// ```
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xffff_u16;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Writes the CRC of all but the last 2 bytes of `buf` into the last 2 bytes.
+///
+/// # Panics
+/// Panics if `buf` is shorter than [`CRC_LEN`].
+pub fn append_crc(buf: &mut [u8]) {
+    let len = buf.len() - CRC_LEN;
+    let crc = crc16(&buf[..len]);
+    buf[len..].copy_from_slice(&crc.to_be_bytes());
+}
+
+/// Checks the CRC in the last 2 bytes of `buf` against the bytes before it.
+///
+/// Returns `false` if `buf` is shorter than [`CRC_LEN`].
+pub fn verify_crc(buf: &[u8]) -> bool {
+    if buf.len() < CRC_LEN {
+        return false;
+    }
+    let len = buf.len() - CRC_LEN;
+    crc16(&buf[..len]).to_be_bytes() == buf[len..]
+}
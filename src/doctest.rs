@@ -0,0 +1,161 @@
+//! Peripherals that do nothing, used to compile the examples in the documentation.
+//!
+//! The examples are compiled but not run, so the peripherals never have to behave like a chip.
+use crate::hal::blocking::delay::{DelayMs, DelayUs};
+use crate::hal::blocking::spi::{Transfer, Write};
+use crate::hal::digital::v2::OutputPin;
+use core::convert::Infallible;
+
+pub use crate::config::*;
+pub use crate::hal::blocking::delay::{DelayMs as _, DelayUs as _};
+#[cfg(feature = "irq")]
+pub use crate::hal::digital::v2::InputPin as _;
+pub use crate::status::*;
+#[cfg(feature = "async")]
+pub use crate::Nrf24l01Async;
+#[cfg(feature = "irq")]
+pub use crate::Nrf24l01WithIrq;
+#[cfg(feature = "payload")]
+pub use crate::Payload;
+pub use crate::{Nrf24l01, TransferError, MAX_PAYLOAD_SIZE};
+
+/// Error returned by the driver built from these peripherals.
+pub type Error = TransferError<Infallible, Infallible>;
+
+/// SPI bus.
+#[derive(Debug, Clone, Copy)]
+pub struct Spi;
+
+impl Transfer<u8> for Spi {
+    type Error = Infallible;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
+        Ok(words)
+    }
+}
+
+impl Write<u8> for Spi {
+    type Error = Infallible;
+
+    fn write(&mut self, _words: &[u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_1::spi::ErrorType for Spi {
+    type Error = Infallible;
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::spi::SpiDevice for Spi {
+    async fn transaction(
+        &mut self,
+        _operations: &mut [embedded_hal_async::spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Pin, usable as output and as input.
+#[derive(Debug, Clone, Copy)]
+pub struct Pin;
+
+impl OutputPin for Pin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "irq")]
+impl crate::hal::digital::v2::InputPin for Pin {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_1::digital::ErrorType for Pin {
+    type Error = Infallible;
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_1::digital::OutputPin for Pin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::digital::Wait for Pin {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Delay that returns immediately.
+#[derive(Debug, Clone, Copy)]
+pub struct Delay;
+
+impl DelayMs<u8> for Delay {
+    fn delay_ms(&mut self, _ms: u8) {}
+}
+
+impl DelayMs<u16> for Delay {
+    fn delay_ms(&mut self, _ms: u16) {}
+}
+
+impl DelayUs<u8> for Delay {
+    fn delay_us(&mut self, _us: u8) {}
+}
+
+impl DelayUs<u16> for Delay {
+    fn delay_us(&mut self, _us: u16) {}
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for Delay {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// Returns the SPI bus, the CE and NCS pins and a delay, to pass to [`Nrf24l01::new()`].
+pub fn peripherals() -> (Spi, Pin, Pin, Delay) {
+    (Spi, Pin, Pin, Delay)
+}
+
+/// Returns a driver that is set up without communicating with the chip, and a delay.
+pub fn setup() -> (Nrf24l01<Spi, Pin, Pin>, Delay) {
+    (Nrf24l01::from_mock(Spi, Pin, Pin), Delay)
+}
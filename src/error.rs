@@ -1,3 +1,4 @@
+use core::fmt;
 #[cfg(feature = "micro-fmt")]
 use ufmt::{uDebug, uWrite, Formatter};
 
@@ -9,9 +10,112 @@ pub enum TransferError<SPIError, PinError> {
     /// Pin set error
     Pin(PinError),
     /// Communication error with module
-    CommunicationError(u8),
+    CommunicationError(ConfigMismatch),
+    /// The chip did not respond, see
+    /// [`is_connected()`](crate::Nrf24l01::is_connected)
+    NotConnected,
     /// Max retries reached
     MaximumRetries,
+    /// The transmission queue is full
+    TxFifoFull,
+    /// The chip is in receive mode, so payloads can't be transmitted. See
+    /// [`stop_listening()`](crate::Nrf24l01::stop_listening).
+    NotInTxMode,
+    /// Acknowledgement payloads are not enabled in the FEATURE register
+    AckPayloadsDisabled,
+    /// The payload at the top of the RX FIFO reported an invalid width. The RX FIFO has been
+    /// flushed.
+    CorruptPayload,
+    /// The status register read back with bit 7 set, which is always 0 on a healthy chip.
+    /// Usually points towards a fault on the SPI bus.
+    InvalidStatus,
+    /// A configuration value is invalid, see [`ConfigError`]
+    Config(ConfigError),
+    /// The configuration of the chip is inconsistent, see [`Inconsistency`]
+    InconsistentConfig(Inconsistency),
+    /// A register read back with reserved bits set, which are always 0 on a healthy chip.
+    /// Contains the value that was read. Usually points towards a fault on the SPI bus.
+    InvalidRegisterValue(u8),
+    /// The address length doesn't match the configured address width
+    InvalidAddressLength {
+        /// Configured address width in bytes
+        expected: u8,
+        /// Length of the supplied address
+        got: u8,
+    },
+    /// A register read back a different value than was written to it
+    ReadbackMismatch {
+        /// The value that was written
+        expected: u8,
+        /// The value that was read back
+        actual: u8,
+    },
+    /// The operation did not complete in time
+    Timeout,
+    /// The buffer holds more bytes than fit in a single payload
+    BufferTooLarge {
+        /// Maximum number of bytes in a payload
+        max: u8,
+    },
+    /// The buffer is shorter than the received payload. The payload is left in the RX FIFO.
+    BufferTooSmall {
+        /// Length of the payload
+        len: u8,
+    },
+    /// The software CRC of a received payload doesn't match, see
+    /// [`crc`](crate::crc). The payload has been removed from the RX FIFO.
+    CrcMismatch,
+    /// The length of a fixed size payload doesn't match the static payload size
+    PayloadSizeMismatch {
+        /// Configured payload size in bytes
+        expected: u8,
+        /// Length of the payload
+        got: u8,
+    },
+    /// Waiting on the IRQ pin failed
+    #[cfg(feature = "async")]
+    InterruptWaitFailed,
+}
+
+/// Register values read back from the chip when its configuration could not be verified.
+///
+/// A `CONFIG` value that doesn't match what was written usually means the chip is not
+/// connected properly. A `SETUP_AW` value of `0x00` or `0xff` points towards a floating or
+/// shorted MISO line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ConfigMismatch {
+    pub(crate) expected: u8,
+    pub(crate) actual: u8,
+    pub(crate) setup_aw: u8,
+}
+
+impl ConfigMismatch {
+    /// The value of the `CONFIG` register that was expected.
+    pub fn expected(&self) -> u8 {
+        self.expected
+    }
+    /// The value of the `CONFIG` register that was read back.
+    pub fn actual(&self) -> u8 {
+        self.actual
+    }
+    /// The value of the `SETUP_AW` register at the time of failure.
+    pub fn setup_aw(&self) -> u8 {
+        self.setup_aw
+    }
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for ConfigMismatch {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        f.debug_struct("ConfigMismatch")?
+            .field("expected", &self.expected)?
+            .field("actual", &self.actual)?
+            .field("setup_aw", &self.setup_aw)?
+            .finish()
+    }
 }
 
 #[cfg(feature = "micro-fmt")]
@@ -24,7 +128,183 @@ impl<SPIError, PinError> uDebug for TransferError<SPIError, PinError> {
             Self::Spi(_) => f.write_str("SPI error"),
             Self::Pin(_) => f.write_str("Pin error"),
             Self::CommunicationError(_) => f.write_str("Communication error"),
+            Self::NotConnected => f.write_str("Not connected"),
             Self::MaximumRetries => f.write_str("Maximum retries reached"),
+            Self::TxFifoFull => f.write_str("TX FIFO full"),
+            Self::NotInTxMode => f.write_str("Not in TX mode"),
+            Self::AckPayloadsDisabled => f.write_str("Acknowledgement payloads disabled"),
+            Self::CorruptPayload => f.write_str("Corrupt payload"),
+            Self::InvalidStatus => f.write_str("Invalid status"),
+            Self::Config(_) => f.write_str("Invalid configuration value"),
+            Self::InconsistentConfig(_) => f.write_str("Inconsistent configuration"),
+            Self::InvalidRegisterValue(_) => f.write_str("Invalid register value"),
+            Self::InvalidAddressLength { .. } => f.write_str("Invalid address length"),
+            Self::ReadbackMismatch { .. } => f.write_str("Register readback mismatch"),
+            Self::Timeout => f.write_str("Timeout"),
+            Self::BufferTooLarge { .. } => f.write_str("Buffer too large"),
+            Self::BufferTooSmall { .. } => f.write_str("Buffer too small"),
+            Self::PayloadSizeMismatch { .. } => f.write_str("Payload size mismatch"),
+            Self::CrcMismatch => f.write_str("CRC mismatch"),
+            #[cfg(feature = "async")]
+            Self::InterruptWaitFailed => f.write_str("Interrupt wait failed"),
+        }
+    }
+}
+
+impl<SPIError, PinError> fmt::Display for TransferError<SPIError, PinError>
+where
+    SPIError: fmt::Debug,
+    PinError: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spi(e) => write!(f, "SPI error: {:?}", e),
+            Self::Pin(e) => write!(f, "pin error: {:?}", e),
+            Self::CommunicationError(m) => write!(
+                f,
+                "CONFIG register read back {:#04x} instead of {:#04x}, SETUP_AW is {:#04x}",
+                m.actual, m.expected, m.setup_aw
+            ),
+            Self::NotConnected => f.write_str("the chip did not respond"),
+            Self::MaximumRetries => f.write_str("maximum retransmit attempts reached"),
+            Self::TxFifoFull => f.write_str("the TX FIFO is full"),
+            Self::NotInTxMode => f.write_str("the chip is not in TX mode"),
+            Self::AckPayloadsDisabled => f.write_str("acknowledgement payloads are disabled"),
+            Self::CorruptPayload => f.write_str("received a payload with an invalid width"),
+            Self::InvalidStatus => f.write_str("the status register has reserved bit 7 set"),
+            Self::Config(e) => write!(f, "invalid configuration: {}", e),
+            Self::InconsistentConfig(e) => write!(f, "inconsistent configuration: {}", e),
+            Self::InvalidRegisterValue(v) => {
+                write!(f, "register read back {:#04x} with reserved bits set", v)
+            }
+            Self::InvalidAddressLength { expected, got } => write!(
+                f,
+                "address is {} bytes long, but the address width is {} bytes",
+                got, expected
+            ),
+            Self::ReadbackMismatch { expected, actual } => write!(
+                f,
+                "register read back {:#04x} after writing {:#04x}",
+                actual, expected
+            ),
+            Self::Timeout => f.write_str("the operation timed out"),
+            Self::BufferTooLarge { max } => {
+                write!(f, "the buffer is larger than the maximum of {} bytes", max)
+            }
+            Self::BufferTooSmall { len } => {
+                write!(f, "the buffer is shorter than the {} byte payload", len)
+            }
+            Self::CrcMismatch => f.write_str("the software CRC of the payload doesn't match"),
+            Self::PayloadSizeMismatch { expected, got } => write!(
+                f,
+                "payload is {} bytes long, but the payload size is {} bytes",
+                got, expected
+            ),
+            #[cfg(feature = "async")]
+            Self::InterruptWaitFailed => f.write_str("waiting on the IRQ pin failed"),
+        }
+    }
+}
+
+/// Combinations of register values that silently break communication.
+///
+/// Returned by [`Nrf24l01::verify_config()`](struct.Nrf24l01.html#method.verify_config).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// Acknowledgement payloads are enabled, but dynamic payloads are not enabled in the
+    /// FEATURE register or on data pipe 0.
+    AckPayloadsWithoutDynamicPayloads,
+    /// Acknowledgement payloads are enabled, but auto acknowledgement is disabled on data
+    /// pipe 0.
+    AckPayloadsWithoutAutoAck,
+    /// Dynamic payloads are enabled on some data pipes, but not in the FEATURE register.
+    DynamicPayloadsWithoutFeature,
+    /// The CRC is disabled, but auto acknowledgement forces it on.
+    CrcForcedByAutoAck,
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for Inconsistency {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        match *self {
+            Self::AckPayloadsWithoutDynamicPayloads => {
+                f.write_str("Ack payloads without dynamic payloads")
+            }
+            Self::AckPayloadsWithoutAutoAck => f.write_str("Ack payloads without auto ack"),
+            Self::DynamicPayloadsWithoutFeature => {
+                f.write_str("Dynamic payloads not enabled in FEATURE")
+            }
+            Self::CrcForcedByAutoAck => f.write_str("CRC forced on by auto ack"),
+        }
+    }
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::AckPayloadsWithoutDynamicPayloads => {
+                "acknowledgement payloads are enabled without dynamic payloads"
+            }
+            Self::AckPayloadsWithoutAutoAck => {
+                "acknowledgement payloads are enabled without auto acknowledgement"
+            }
+            Self::DynamicPayloadsWithoutFeature => {
+                "dynamic payloads are not enabled in the FEATURE register"
+            }
+            Self::CrcForcedByAutoAck => {
+                "the CRC is disabled, but forced on by auto acknowledgement"
+            }
+        })
+    }
+}
+
+/// Errors that can occur when validating configuration values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The frequency in MHz lies outside of the 2400..=2525 MHz range of the chip.
+    FrequencyOutOfRange(u16),
+    /// The WiFi channel is not in the 1..=14 range.
+    InvalidWifiChannel(u8),
+    /// More addresses were given than there are data pipes. Contains the number of addresses.
+    TooManyPipes(u8),
+    /// The same address was given to more than one data pipe. Contains the least significant
+    /// byte of the address.
+    DuplicateAddress(u8),
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for ConfigError {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        match *self {
+            Self::FrequencyOutOfRange(_) => f.write_str("Frequency out of range"),
+            Self::InvalidWifiChannel(_) => f.write_str("Invalid WiFi channel"),
+            Self::TooManyPipes(_) => f.write_str("Too many pipes"),
+            Self::DuplicateAddress(_) => f.write_str("Duplicate address"),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FrequencyOutOfRange(mhz) => {
+                write!(f, "frequency of {} MHz is outside of 2400..=2525 MHz", mhz)
+            }
+            Self::InvalidWifiChannel(ch) => {
+                write!(f, "WiFi channel {} is outside of 1..=14", ch)
+            }
+            Self::TooManyPipes(n) => write!(f, "{} addresses don't fit in the data pipes", n),
+            Self::DuplicateAddress(lsb) => write!(
+                f,
+                "address with least significant byte {:#04x} is used more than once",
+                lsb
+            ),
         }
     }
 }
@@ -0,0 +1,165 @@
+//! Driver with an IRQ pin.
+use crate::error::TransferError;
+use crate::hal::blocking::spi::{Transfer, Write};
+use crate::hal::digital::v2::{InputPin, OutputPin};
+use crate::nrf24::Nrf24l01;
+use crate::status::Interrupts;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+/// The nRF24L01 driver together with the IRQ pin of the chip.
+///
+/// The IRQ pin is pulled low by the chip as long as one of the unmasked interrupt flags is set,
+/// see [`set_interrupts()`](struct.Nrf24l01.html#method.set_interrupts). Checking the pin first
+/// saves an SPI transaction when nothing happened.
+///
+/// Dereferences to [`Nrf24l01`], so all methods of the driver are available.
+///
+/// # Examples
+/// ```no_run
+/// # use nrf24_rs::doctest::*;
+/// # let (spi, ce, ncs, mut delay) = peripherals();
+/// # let irq = Pin;
+/// let chip = Nrf24l01::new(spi, ce, ncs, &mut delay, NrfConfig::default())?;
+/// let mut chip = Nrf24l01WithIrq::new(chip, irq);
+/// chip.open_reading_pipe(DataPipe::DP1, b"Node1")?;
+/// chip.start_listening()?;
+/// loop {
+///     if let Some(interrupts) = chip.poll_irq()? {
+///         if interrupts.contains(InterruptKind::DataReady) {
+///             let mut buffer = [0; 32];
+///             let n = chip.read(&mut buffer)?;
+///         }
+///     }
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub struct Nrf24l01WithIrq<SPI, CE, NCS, IRQ> {
+    chip: Nrf24l01<SPI, CE, NCS>,
+    // IRQ pin, active low
+    irq: IRQ,
+}
+
+impl<SPI, CE, NCS, IRQ> Nrf24l01WithIrq<SPI, CE, NCS, IRQ> {
+    /// Combines an initialized driver with the IRQ pin of the chip.
+    ///
+    /// The interrupt configuration of the chip is left as is, see
+    /// [`setup_interrupts()`](struct.Nrf24l01.html#method.setup_interrupts) to also enable the
+    /// interrupts and clear stale flags.
+    pub fn new(chip: Nrf24l01<SPI, CE, NCS>, irq: IRQ) -> Self {
+        Self { chip, irq }
+    }
+
+    /// Returns the driver and the IRQ pin.
+    pub fn release(self) -> (Nrf24l01<SPI, CE, NCS>, IRQ) {
+        (self.chip, self.irq)
+    }
+}
+
+impl<SPI, CE, NCS, IRQ, SPIErr, PinErr> Nrf24l01WithIrq<SPI, CE, NCS, IRQ>
+where
+    SPI: Transfer<u8, Error = SPIErr> + Write<u8, Error = SPIErr>,
+    NCS: OutputPin<Error = PinErr>,
+    CE: OutputPin<Error = PinErr>,
+    IRQ: InputPin<Error = PinErr>,
+{
+    /// Returns `true` if the IRQ pin is low, meaning an interrupt is pending.
+    ///
+    /// Doesn't use the SPI bus.
+    pub fn irq_asserted(&self) -> Result<bool, TransferError<SPIErr, PinErr>> {
+        self.irq.is_low().map_err(TransferError::Pin)
+    }
+
+    /// Returns the triggered interrupts and clears them, or `None` if no interrupt is pending.
+    ///
+    /// The status register is only read when the IRQ pin is low. Only the interrupt flags that
+    /// were read are cleared, so an interrupt that comes in right after the read is not lost.
+    ///
+    /// # Notes
+    /// The IRQ pin only reflects the unmasked interrupts, masked interrupts that triggered at
+    /// the same time are returned as well.
+    pub fn poll_irq(&mut self) -> Result<Option<Interrupts>, TransferError<SPIErr, PinErr>> {
+        if !self.irq_asserted()? {
+            return Ok(None);
+        }
+        let interrupts = Interrupts::from(self.chip.status()?.value());
+        if interrupts == Interrupts::new() {
+            return Ok(None);
+        }
+        self.chip.clear_interrupts(interrupts)?;
+        Ok(Some(interrupts))
+    }
+}
+
+impl<SPI, CE, NCS, IRQ> Deref for Nrf24l01WithIrq<SPI, CE, NCS, IRQ> {
+    type Target = Nrf24l01<SPI, CE, NCS>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.chip
+    }
+}
+
+impl<SPI, CE, NCS, IRQ> DerefMut for Nrf24l01WithIrq<SPI, CE, NCS, IRQ> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.chip
+    }
+}
+
+impl<SPI, CE, NCS, IRQ> fmt::Debug for Nrf24l01WithIrq<SPI, CE, NCS, IRQ>
+where
+    SPI: fmt::Debug,
+    CE: fmt::Debug,
+    NCS: fmt::Debug,
+    IRQ: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Nrf24l01WithIrq")
+            .field("chip", &self.chip)
+            .field("irq", &self.irq)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{PinMock, SpiMock, Transaction};
+
+    #[test]
+    fn poll_irq_only_reads_status_when_irq_is_low() {
+        let spi = SpiMock::new(&[
+            Transaction::transfer(&[0xff], &[0b0100_0010]),
+            Transaction::write(&[0x27, 0b0100_0000]),
+        ]);
+        let mut irq = PinMock::default();
+        irq.set_high().unwrap();
+        let chip = Nrf24l01::from_mock(spi.clone(), PinMock::default(), PinMock::default());
+        let mut chip = Nrf24l01WithIrq::new(chip, irq.clone());
+        assert_eq!(chip.poll_irq().unwrap(), None);
+        irq.set_low().unwrap();
+        assert_eq!(
+            chip.poll_irq().unwrap(),
+            Some(Interrupts::new().data_ready())
+        );
+        spi.done();
+    }
+
+    #[test]
+    fn setup_interrupts_stores_irq_pin() {
+        let spi = SpiMock::new(&[
+            // Only data ready is signaled on the pin
+            Transaction::write(&[0x20, 0b0011_1110]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+        ]);
+        let mut irq = PinMock::default();
+        irq.set_high().unwrap();
+        let chip = Nrf24l01::from_mock(spi.clone(), PinMock::default(), PinMock::default());
+        let chip = chip
+            .setup_interrupts(Interrupts::new().data_ready(), irq.clone())
+            .unwrap();
+        assert!(!chip.irq_asserted().unwrap());
+        irq.set_low().unwrap();
+        assert!(chip.irq_asserted().unwrap());
+        spi.done();
+    }
+}
@@ -16,142 +16,104 @@
 //!
 //! # Example: Sending data
 //! This simple example will send a simple "Hello world" message.
-//! ```rust
-//! use panic_halt as _;
-//!
-//! use atmega168_hal as hal;
-//! use hal::prelude::*;
-//! use hal::spi;
-//! use nrf24_rs::config::{NrfConfig, PALevel};
-//! use nrf24_rs::{Nrf24l01, SPI_MODE};
-//!
-//! #[atmega168_hal::entry]
-//! fn main() -> ! {
-//!     // Take peripherals
-//!     let dp = hal::pac::Peripherals::take().unwrap();
-//!
-//!     // Initialize the different pins
-//!     let mut portb = dp.PORTB.split();
-//!     let ncs = portb.pb2.into_output(&mut portb.ddr);
-//!     let mosi = portb.pb3.into_output(&mut portb.ddr);
-//!     let miso = portb.pb4.into_pull_up_input(&mut portb.ddr);
-//!     let sclk = portb.pb5.into_output(&mut portb.ddr);
-//!
-//!     // Initialize SPI
-//!     let settings = spi::Settings {
-//!         data_order: spi::DataOrder::MostSignificantFirst,
-//!         clock: spi::SerialClockRate::OscfOver4,
-//!         mode: SPI_MODE, // SPI Mode defined in this crate
-//!     };
-//!     let (spi, ncs) = spi::Spi::new(dp.SPI, sclk, mosi, miso, ncs, settings);
-//!
-//!     let mut delay = hal::delay::Delay::<hal::clock::MHz16>::new();
-//!
-//!     let message = b"Hello world!"; // The message we will be sending
-//!
-//!     // Setup some configuration values
-//!     let config = NrfConfig::default()
-//!         .channel(8)
-//!         .pa_level(PALevel::Min)
-//!         // We will use a payload size the size of our message
-//!         .payload_size(message.len());
-//!
-//!     // Initialize the chip
-//!     let mut nrf_chip = Nrf24l01::New(spi, ce, ncs, &mut delay, config).unwrap();
-//!     if !nrf_chip.is_connected().unwrap() {
-//!         panic!("Chip is not connected.");
-//!     }
-//!
-//!     // Open a writing pipe on address "Node1".
-//!     // The listener will have to open a reading pipe with the same address
-//!     // in order to recieve this message.
-//!     nrf.open_writing_pipe(b"Node1").unwrap();
-//!
-//!     // Keep trying to send the message
-//!     while let Err(e) = nrf.write(&mut delay, &message) {
-//!         // Something went wrong while writing, try again in 50ms
-//!         delay.delay_ms(50u16);
-//!     }
-//!
-//!     // Message should now successfully have been sent!
-//!     loop {}
+//! ```no_run
+//! # #![cfg_attr(not(feature = "test-util"), no_main, cfg(any()))]
+//! use nrf24_rs::config::{NrfConfig, PALevel, PayloadSize};
+//! use nrf24_rs::Nrf24l01;
+//! # use nrf24_rs::doctest::*;
+//! # fn main() -> Result<(), Error> {
+//! # let (spi, ce, ncs, mut delay) = peripherals();
+//!
+//! // The HAL of the microcontroller provides:
+//! // - `spi`, an SPI instance using the SPI mode specified in this crate, `nrf24_rs::SPI_MODE`
+//! // - `ce`, the Chip Enable output pin
+//! // - `ncs`, the Chip Select output pin (active low)
+//! // - `delay`, a delay implementation
+//!
+//! let message = b"Hello world!"; // The message we will be sending
+//!
+//! // Setup some configuration values
+//! let config = NrfConfig::default()
+//!     .channel(8)
+//!     .pa_level(PALevel::Min)
+//!     // We will use a payload size the size of our message
+//!     .payload_size(PayloadSize::Static(message.len() as u8));
+//!
+//! // Initialize the chip, this also checks that it is connected
+//! let mut nrf_chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config)?;
+//!
+//! // Open a writing pipe on address "Node1".
+//! // The listener will have to open a reading pipe with the same address
+//! // in order to recieve this message.
+//! nrf_chip.open_writing_pipe(b"Node1")?;
+//!
+//! // Keep trying to send the message
+//! while let Err(e) = nrf_chip.write(&mut delay, message) {
+//!     // Something went wrong while writing, try again in 50ms
+//!     delay.delay_ms(50u8);
 //! }
+//!
+//! // Message should now successfully have been sent!
+//! # Ok(())
+//! # }
 //! ```
 //!
 //!
 //! # Example: Reading data
 //! This simple example will read a "Hello world" message.
-//! ```rust
-//! use panic_halt as _;
-//!
-//! use atmega168_hal as hal;
-//! use hal::prelude::*;
-//! use hal::spi;
-//! use nrf24_rs::config::{NrfConfig, PALevel, DataPipe};
-//! use nrf24_rs::{Nrf24l01, SPI_MODE};
-//!
-//! #[atmega168_hal::entry]
-//! fn main() -> ! {
-//!     // Take peripherals
-//!     let dp = hal::pac::Peripherals::take().unwrap();
-//!
-//!     // Initialize the different pins
-//!     let mut portb = dp.PORTB.split();
-//!     let ncs = portb.pb2.into_output(&mut portb.ddr);
-//!     let mosi = portb.pb3.into_output(&mut portb.ddr);
-//!     let miso = portb.pb4.into_pull_up_input(&mut portb.ddr);
-//!     let sclk = portb.pb5.into_output(&mut portb.ddr);
-//!
-//!     // Initialize SPI
-//!     let settings = spi::Settings {
-//!         data_order: spi::DataOrder::MostSignificantFirst,
-//!         clock: spi::SerialClockRate::OscfOver4,
-//!         mode: SPI_MODE, // SPI Mode defined in this crate
-//!     };
-//!     let (spi, ncs) = spi::Spi::new(dp.SPI, sclk, mosi, miso, ncs, settings);
-//!
-//!     let mut delay = hal::delay::Delay::<hal::clock::MHz16>::new();
-//!
-//!     // Setup some configuration values
-//!     let config = NrfConfig::default()
-//!         .channel(8)
-//!         .pa_level(PALevel::Min)
-//!         // We will use a payload size the size of our message
-//!         .payload_size(b"Hello world!".len());
-//!
-//!     // Initialize the chip
-//!     let mut nrf_chip = Nrf24l01::New(spi, ce, ncs, &mut delay, config).unwrap();
-//!     if !nrf_chip.is_connected().unwrap() {
-//!         panic!("Chip is not connected.");
-//!     }
-//!
-//!     // Open reading pipe 0 with address "Node1".
-//!     // The sender will have to open its writing pipe with the same address
-//!     // in order to transmit this message successfully.
-//!     nrf_chip.open_reading_pipe(DataPipe::DP0, b"Node1").unwrap();
-//!     // Set the chip in RX mode
-//!     nrf_chip.start_listening().unwrap();
-//!
-//!     // Keep checking if there is any data available to read
-//!     while !nrf_chip.data_available().unwrap() {
-//!         // No data availble, wait 50ms, then check again
-//!         delay.delay_ms(50u16);
-//!     }
-//!     // Now there is some data availble to read
-//!
-//!     // Initialize empty buffer
-//!     let mut buffer = [0; b"Hello world!".len()];
-//!     nrf_chip.read(&mut buffer).unwrap();
-//!
-//!     assert_eq!(buffer, b"Hello world!");
-//!
-//!     loop {}
+//! ```no_run
+//! # #![cfg_attr(not(feature = "test-util"), no_main, cfg(any()))]
+//! use nrf24_rs::config::{DataPipe, NrfConfig, PALevel, PayloadSize};
+//! use nrf24_rs::Nrf24l01;
+//! # use nrf24_rs::doctest::*;
+//! # fn main() -> Result<(), Error> {
+//! # let (spi, ce, ncs, mut delay) = peripherals();
+//!
+//! // The HAL of the microcontroller provides `spi`, `ce`, `ncs` and `delay`,
+//! // see the previous example.
+//!
+//! // Setup some configuration values
+//! let config = NrfConfig::default()
+//!     .channel(8)
+//!     .pa_level(PALevel::Min)
+//!     // We will use a payload size the size of our message
+//!     .payload_size(PayloadSize::Static(b"Hello world!".len() as u8));
+//!
+//! // Initialize the chip, this also checks that it is connected
+//! let mut nrf_chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config)?;
+//!
+//! // Open reading pipe 0 with address "Node1".
+//! // The sender will have to open its writing pipe with the same address
+//! // in order to transmit this message successfully.
+//! nrf_chip.open_reading_pipe(DataPipe::DP0, b"Node1")?;
+//! // Set the chip in RX mode
+//! nrf_chip.start_listening()?;
+//!
+//! // Keep checking if there is any data available to read
+//! while !nrf_chip.data_available()? {
+//!     // No data availble, wait 50ms, then check again
+//!     delay.delay_ms(50u8);
 //! }
+//! // Now there is some data availble to read
+//!
+//! // Initialize empty buffer
+//! let mut buffer = [0; b"Hello world!".len()];
+//! nrf_chip.read(&mut buffer)?;
+//!
+//! assert_eq!(&buffer, b"Hello world!");
+//! # Ok(())
+//! # }
 //! ```
 //!
 //! # Feature-flags
 //!
+//! - **async:** provides `Nrf24l01Async`, a driver built on the [embedded-hal-async](https://docs.rs/embedded-hal-async) traits that can wait for the IRQ pin without blocking. Needs Rust 1.75.
+//! - **irq:** provides `Nrf24l01WithIrq`, which pairs the driver with the IRQ pin of the chip. Enables the `unproven` feature of `embedded-hal` for the `InputPin` trait.
 //! - **micro-fmt:** provides a `uDebug` implementation from the [ufmt crate](https://docs.rs/ufmt) for all public structs and enums.
+//! - **payload:** provides `Payload`, a payload with a length known at compile time, and `Nrf24l01::write_payload` to send it. Uses const generics, which need Rust 1.51.
+//! - **serde:** derives `Serialize` and `Deserialize` from the [serde crate](https://docs.rs/serde) for `NrfConfig` and the configuration values it holds, so a configuration can be stored and restored. Deserialized values are clipped like the `NrfConfig` builder methods do. Needs the Rust version of the serde release in use, which is newer than the minimum supported Rust version of this crate (1.71 for recent releases).
+//! - **test-util:** provides `Nrf24l01::from_mock` to construct a driver without communicating with the chip, for testing code built on top of this driver. The examples in this documentation are only checked with this feature, use `cargo test --features async,irq,payload,test-util` to run all of them.
+//! - **unstable-raw:** provides `Nrf24l01::read_register_raw` and `Nrf24l01::write_register_raw` for direct register access. Not covered by semver, the driver assumes it is the only one changing the registers.
 #![warn(
     missing_docs,
     missing_copy_implementations,
@@ -163,14 +125,38 @@
 extern crate embedded_hal as hal;
 use hal::spi;
 
+// The examples of the driver use the `doctest` module, which needs the `test-util` feature.
+// Without it they compile to nothing, run them with `cargo test --features async,irq,payload,test-util`.
+#[cfg(feature = "async")]
+#[cfg_attr(not(feature = "test-util"), doc(test(attr(no_main, cfg(any())))))]
+mod asynch;
+pub mod channels;
 pub mod config;
+pub mod crc;
+#[cfg(feature = "test-util")]
+#[doc(hidden)]
+pub mod doctest;
 mod error;
+#[cfg(feature = "irq")]
+#[cfg_attr(not(feature = "test-util"), doc(test(attr(no_main, cfg(any())))))]
+mod irq;
+#[cfg(test)]
+mod mock;
+#[cfg_attr(not(feature = "test-util"), doc(test(attr(no_main, cfg(any())))))]
 mod nrf24;
+#[cfg(feature = "payload")]
+mod payload;
 mod register_acces;
 pub mod status;
 
-pub use crate::error::TransferError;
+#[cfg(feature = "async")]
+pub use crate::asynch::Nrf24l01Async;
+pub use crate::error::{ConfigError, ConfigMismatch, Inconsistency, TransferError};
+#[cfg(feature = "irq")]
+pub use crate::irq::Nrf24l01WithIrq;
 pub use crate::nrf24::Nrf24l01;
+#[cfg(feature = "payload")]
+pub use crate::payload::Payload;
 
 /// SPI mode. Use this when initializing the SPI instance.
 pub const SPI_MODE: spi::Mode = spi::MODE_0;
@@ -0,0 +1,245 @@
+//! Mock implementations of the embedded-hal traits, used to test the SPI transactions of the
+//! driver.
+//!
+//! The SPI mock is created with the list of transactions it expects, in order, and panics as
+//! soon as the driver does something else. Clones share their state, so a clone can be handed
+//! to the driver while the original is kept around to call [`SpiMock::done()`].
+extern crate std;
+
+use crate::hal::blocking::delay::{DelayMs, DelayUs};
+use crate::hal::blocking::spi::{Transfer, Write};
+#[cfg(feature = "irq")]
+use crate::hal::digital::v2::InputPin;
+use crate::hal::digital::v2::OutputPin;
+use core::cell::{Cell, RefCell};
+use core::convert::Infallible;
+#[cfg(feature = "async")]
+use core::future::Future;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::{collections::VecDeque, rc::Rc, vec::Vec};
+
+/// A single SPI transaction the mock expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Transaction {
+    /// Full duplex transfer of the expected bytes, answered with the response.
+    Transfer(Vec<u8>, Vec<u8>),
+    /// Write of the expected bytes.
+    Write(Vec<u8>),
+}
+
+impl Transaction {
+    pub(crate) fn transfer(expected: &[u8], response: &[u8]) -> Self {
+        assert_eq!(expected.len(), response.len(), "response length mismatch");
+        Self::Transfer(expected.to_vec(), response.to_vec())
+    }
+    pub(crate) fn write(expected: &[u8]) -> Self {
+        Self::Write(expected.to_vec())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SpiMock {
+    expectations: Rc<RefCell<VecDeque<Transaction>>>,
+}
+
+impl SpiMock {
+    pub(crate) fn new(expectations: &[Transaction]) -> Self {
+        Self {
+            expectations: Rc::new(RefCell::new(expectations.iter().cloned().collect())),
+        }
+    }
+
+    /// Asserts that all expected transactions have happened.
+    pub(crate) fn done(&self) {
+        let remaining = self.expectations.borrow();
+        assert!(
+            remaining.is_empty(),
+            "expected transactions did not happen: {:?}",
+            remaining
+        );
+    }
+
+    fn next(&mut self) -> Transaction {
+        self.expectations
+            .borrow_mut()
+            .pop_front()
+            .expect("no more transactions expected")
+    }
+}
+
+impl Transfer<u8> for SpiMock {
+    type Error = Infallible;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
+        match self.next() {
+            Transaction::Transfer(expected, response) => {
+                assert_eq!(&words[..], &expected[..], "unexpected transfer");
+                words.copy_from_slice(&response);
+                Ok(words)
+            }
+            other => panic!("expected {:?}, got transfer of {:?}", other, words),
+        }
+    }
+}
+
+impl Write<u8> for SpiMock {
+    type Error = Infallible;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        match self.next() {
+            Transaction::Write(expected) => {
+                assert_eq!(words, &expected[..], "unexpected write");
+                Ok(())
+            }
+            other => panic!("expected {:?}, got write of {:?}", other, words),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_1::spi::ErrorType for SpiMock {
+    type Error = Infallible;
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::spi::SpiDevice for SpiMock {
+    async fn transaction(
+        &mut self,
+        operations: &mut [embedded_hal_async::spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        use embedded_hal_async::spi::Operation;
+        for operation in operations {
+            match operation {
+                Operation::Write(words) => Write::write(self, words)?,
+                Operation::TransferInPlace(words) => {
+                    Transfer::transfer(self, words)?;
+                }
+                _ => panic!("unexpected SPI operation"),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Pin that keeps track of its level, usable as output and as input.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PinMock {
+    high: Rc<Cell<bool>>,
+}
+
+impl PinMock {
+    pub(crate) fn is_high(&self) -> bool {
+        self.high.get()
+    }
+}
+
+impl OutputPin for PinMock {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.high.set(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.high.set(true);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "irq")]
+impl InputPin for PinMock {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        Ok(self.high.get())
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        Ok(!self.high.get())
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_1::digital::ErrorType for PinMock {
+    type Error = Infallible;
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_1::digital::OutputPin for PinMock {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.high.set(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.high.set(true);
+        Ok(())
+    }
+}
+
+/// Waiting returns immediately and sets the pin to the level that was waited for, as if the
+/// chip changed it.
+#[cfg(feature = "async")]
+impl embedded_hal_async::digital::Wait for PinMock {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.high.set(true);
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.high.set(false);
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_high().await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_low().await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.high.set(!self.high.get());
+        Ok(())
+    }
+}
+
+/// Delay that returns immediately.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DelayMock;
+
+impl DelayMs<u8> for DelayMock {
+    fn delay_ms(&mut self, _ms: u8) {}
+}
+
+impl DelayUs<u8> for DelayMock {
+    fn delay_us(&mut self, _us: u8) {}
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for DelayMock {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// Runs a future to completion. The mocks never wait, so the future is ready when first polled.
+#[cfg(feature = "async")]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    fn raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            raw_waker()
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(core::ptr::null(), &VTABLE)
+    }
+    // Safety: the vtable functions do nothing, so the contract of RawWaker is upheld
+    let waker = unsafe { Waker::from_raw(raw_waker()) };
+    let mut future = core::pin::pin!(future);
+    match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is not ready"),
+    }
+}
@@ -1,19 +1,30 @@
 //! nRF24 implementations.
 
 use crate::config::{
-    AddressWidth, AutoRetransmission, DataPipe, DataRate, EncodingScheme, Mode, NrfConfig, PALevel,
-    PayloadSize,
+    channel_for_frequency, AddressWidth, AutoRetransmission, ChipVariant, DataPipe, DataRate,
+    EncodingScheme, Features, Mode, NrfConfig, PALevel, PayloadSize, BASE_FREQUENCY, MAX_CHANNEL,
+    MIN_CE_PULSE_US,
 };
-use crate::error::TransferError;
+use crate::crc::{self, CRC_LEN};
+use crate::error::{ConfigError, ConfigMismatch, Inconsistency, TransferError};
 use crate::hal::blocking::{
     delay::DelayMs,
     delay::DelayUs,
     spi::{Transfer, Write},
 };
 
+#[cfg(feature = "irq")]
+use crate::hal::digital::v2::InputPin;
 use crate::hal::digital::v2::OutputPin;
 use crate::register_acces::{Instruction, Register};
-use crate::status::{Interrupts, Status};
+use crate::status::{
+    ConfigRegister, FIFOStatus, Interrupts, IrqPinConfig, LinkQuality, ObserveTx, Status,
+    WriteOutcome,
+};
+#[cfg(feature = "irq")]
+use crate::Nrf24l01WithIrq;
+#[cfg(feature = "payload")]
+use crate::Payload;
 use crate::MAX_PAYLOAD_SIZE;
 use core::fmt;
 
@@ -22,13 +33,16 @@ use core::fmt;
 /// For the different configuration options see: [`NrfConfig`].
 ///
 /// # Examples
-/// ```
-/// use nrf24::Nrf24l01;
-/// use nrf24::config::NrfConfig;
+/// ```no_run
+/// # use nrf24_rs::doctest::*;
+/// # let (spi, ce, ncs, mut delay) = peripherals();
+/// use nrf24_rs::Nrf24l01;
+/// use nrf24_rs::config::NrfConfig;
 ///
 /// // Initialize the chip with deafault configuration.
 /// let nrf24 = Nrf24l01::new(spi, ce, ncs, &mut delay, NrfConfig::default()).unwrap();
 ///
+/// # Ok::<(), Error>(())
 /// ```
 pub struct Nrf24l01<SPI, CE, NCS> {
     spi: SPI,
@@ -36,12 +50,39 @@ pub struct Nrf24l01<SPI, CE, NCS> {
     ncs: NCS,
     // Chip Enable Pin
     ce: CE,
+    // Whether the Chip Enable Pin is driven high
+    ce_high: bool,
     // Config Register
     config_reg: u8,
     // Payload size
     payload_size: PayloadSize,
+    // Static payload width of every receiving pipe, written to a pipe when it's opened
+    rx_payload_widths: [u8; 6],
+    // Whether the receiving pipes use different static payload widths
+    per_pipe_widths: bool,
+    // Address width
+    addr_width: AddressWidth,
+    // Data rate
+    data_rate: DataRate,
     // Transmission buffer
     tx_buf: [u8; MAX_PAYLOAD_SIZE as usize + 1],
+    // Number of times a failed SPI transaction is retried
+    spi_retries: u8,
+    // Time in µs that CE is held high to start a transmission
+    ce_pulse_us: u8,
+    // Number of packets transmitted
+    tx_packets: u32,
+    // Total number of retransmissions of all packets transmitted
+    tx_retransmits: u32,
+    // Whether the retransmissions of the last packet still need to be accounted for
+    tx_pending: bool,
+    // Status received during the last SPI transfer
+    last_status: Status,
+    // Whether a software CRC is added to payloads when the hardware CRC is disabled
+    software_crc: bool,
+    // Payload already read from the RX FIFO by `peek_header`, with its status and length
+    peeked: Option<(Status, u8)>,
+    peek_buf: [u8; MAX_PAYLOAD_SIZE as usize],
 }
 
 //type Result<T, E, F> = core::result::Result<T, Error<E, F>>;
@@ -53,40 +94,48 @@ where
     CE: OutputPin<Error = PinErr>,
 {
     const MAX_ADDR_WIDTH: usize = 5;
-    const CORRECT_CONFIG: u8 = 0b00001110;
-    const STATUS_RESET: u8 = 0b01110000;
+    // Value of the status register after a reset, the RX FIFO is empty.
+    const STATUS_DEFAULT: u8 = 0b0000_1110;
+    // Interval and maximum number of polls while waiting for a transmission to finish.
+    // Covers the worst case of 15 retransmissions with the maximum delay of 4000µs.
+    const TX_POLL_INTERVAL_US: u8 = 100;
+    const TX_POLL_COUNT: u32 = 1000;
+    // Number of payloads the TX FIFO holds.
+    const TX_FIFO_DEPTH: usize = 3;
+    // Interval between polls while waiting for a payload to arrive.
+    const RX_POLL_INTERVAL_MS: u8 = 1;
+    // Time in receive mode before the received power detector is valid, Tstby2a + Tdelay_AGC.
+    const RPD_SETTLE_US: u8 = 170;
+    // RF_SETUP bits for constant carrier output.
+    const CONT_WAVE: u8 = 1 << 7;
+    const PLL_LOCK: u8 = 1 << 4;
+    // Reserved bits that always read back as 0.
+    const RF_SETUP_RESERVED: u8 = 1 << 6;
+    const CONFIG_RESERVED: u8 = 1 << 7;
+    // RF_SETUP bit for the low noise amplifier gain on the nRF24L01.
+    const LNA_HCURR: u8 = 1 << 0;
 
     /// Creates a new nrf24l01 driver with given config.
     /// Starts up the device after initialization, so calling [`power_up()`](#method.power_up) is not necessary.
     ///
-    /// # Examples
-    /// ```
-    /// // Initialize all pins required
-    /// let dp = Peripherals::take()::unwrap();
-    /// let mut portd = dp.PORTD.split();
-    /// let ce = portd.pd3.into_output(&mut portd.ddr); // Chip Enable
-    ///
-    /// let mut portb = dp.PORTB.split();
-    /// let ncs = portb.pb2.into_output(&mut portb.ddr); // Chip Select (active low)
-    /// let mosi = portb.pb3.into_output(&mut portb.ddr); // Master Out Slave In Pin
-    /// let miso = portb.pb4.into_pull_up_input(&mut portb.ddr); // Master In Slave Out Pin
-    /// let sclk = portb.pb5.into_output(&mut portb.ddr); // Clock
+    /// Returns [`TransferError::NotConnected`] if the chip doesn't respond, see
+    /// [`is_connected()`](#method.is_connected), and [`TransferError::CommunicationError`] if the
+    /// CONFIG register doesn't read back what was written.
     ///
-    /// // Now we initialize SPI settings to create an SPI instance
-    /// let settings = spi::Settings {
-    ///     data_order: DataOrder::MostSignificantFirst,
-    ///     clock: SerialClockRate::OscfOver4,
-    ///     // The required SPI mode for communication with the nrf chip is specified in
-    ///     // this crate
-    ///     mode: nrf24_rs::SPI_MODE,
-    /// };
-    /// let (spi, ncs) = spi::Spi::new(dp.SPI, sclk, mosi, miso, ncs, settings);
-    /// let mut delay = hal::delay::Delay::<clock::MHz16>::new();
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// // The HAL of the microcontroller provides:
+    /// // - `spi`, an SPI instance using the SPI mode specified in this crate, `nrf24_rs::SPI_MODE`
+    /// // - `ce`, the Chip Enable output pin
+    /// // - `ncs`, the Chip Select output pin (active low)
+    /// // - `delay`, a delay implementation
     ///
     /// // Construct a new instance of the chip with a default configuration
     /// // This will initialize the module and start it up
     /// let nrf24 = nrf24_rs::Nrf24l01::new(spi, ce, ncs, &mut delay, NrfConfig::default())?;
-    ///
+    /// # Ok::<(), Error>(())
     /// ```
     pub fn new<D>(
         spi: SPI,
@@ -98,14 +147,9 @@ where
     where
         D: DelayMs<u8>,
     {
-        let mut chip = Nrf24l01 {
-            spi,
-            ncs,
-            ce,
-            config_reg: 0,
-            payload_size: PayloadSize::Static(0),
-            tx_buf: [0; MAX_PAYLOAD_SIZE as usize + 1],
-        };
+        let mut chip = Self::from_parts(spi, ce, ncs);
+        chip.spi_retries = config.spi_retries;
+        chip.ce_pulse_us = config.ce_pulse_us;
 
         // Set the output pins to the correct levels
         chip.set_ce_low()?;
@@ -118,12 +162,25 @@ where
         // Technically we require 4.5ms + 14us as a worst case. We'll just call it 5ms for good measure.
         delay.delay_ms(5);
 
+        // Fail fast on a floating or shorted bus, before configuring anything
+        if !chip.is_connected()? {
+            return Err(TransferError::NotConnected);
+        }
+
         // Set retries
         chip.set_retries(config.auto_retry)?;
         // Set rf
         chip.setup_rf(config.data_rate, config.pa_level)?;
         // Set payload size
         chip.set_payload_size(config.payload_size)?;
+        // Enable acknowledgement payloads, which also enables dynamic payloads
+        if config.ack_payloads_enabled {
+            chip.enable_ack_payloads()?;
+        }
+        // Allow payloads without acknowledgement
+        if config.dynamic_ack {
+            chip.enable_dynamic_ack()?;
+        }
         // Set address length
         chip.set_address_width(config.addr_width)?;
         // Reset status
@@ -134,108 +191,426 @@ where
         chip.flush_rx()?;
         chip.flush_tx()?;
 
-        // clear CONFIG register, Enable PTX, Power Up & 16-bit CRC
-        if let Some(encoding_scheme) = config.crc_encoding_scheme {
-            chip.enable_crc(encoding_scheme)?;
-        }
+        // clear CONFIG register, Enable PTX, configure the CRC & mask interrupts
+        let config_val =
+            Self::crc_config(config.crc_encoding_scheme) | config.masked_interrupts.value();
+        chip.write_register(Register::CONFIG, config_val)?;
 
         chip.config_reg = chip.read_register(Register::CONFIG)?;
 
         chip.power_up(delay)?;
 
-        if chip.config_reg != Self::CORRECT_CONFIG {
-            Err(TransferError::CommunicationError(chip.config_reg))
+        let expected = config_val | (1 << 1);
+        if chip.config_reg != expected {
+            Err(TransferError::CommunicationError(ConfigMismatch {
+                expected,
+                actual: chip.config_reg,
+                setup_aw: chip.read_register(Register::SETUP_AW)?,
+            }))
         } else {
             Ok(chip)
         }
     }
 
+    /// Creates a driver in a known state without communicating with the chip.
+    ///
+    /// Skips the settling delays and the configuration check of [`new()`](#method.new), and
+    /// assumes the chip is powered up in transmission mode with the default configuration.
+    /// No SPI transactions are made, so the SPI transactions of subsequent calls can be
+    /// asserted against a mock.
+    ///
+    /// Only available with the `test-util` feature.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi_mock, ce_mock, ncs_mock, _) = peripherals();
+    /// let mut chip = Nrf24l01::from_mock(spi_mock, ce_mock, ncs_mock);
+    /// chip.set_channel(10)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn from_mock(spi: SPI, ce: CE, ncs: NCS) -> Self {
+        let mut chip = Self::from_parts(spi, ce, ncs);
+        // Powered up with the default CRC
+        chip.config_reg = Self::crc_config(NrfConfig::default().crc_encoding_scheme) | (1 << 1);
+        chip.payload_size = PayloadSize::default();
+        chip.rx_payload_widths = [MAX_PAYLOAD_SIZE; 6];
+        chip
+    }
+
     /// Checks if the chip is connected to the SPI bus.
+    ///
+    /// Writes two different address widths to the SETUP_AW register and reads them back, so a
+    /// floating or stuck MISO line can't pass the check. The original value is restored.
+    ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
     /// if !chip.is_connected()? {
     ///     // Handle disconnection
     /// }
+    /// # Ok::<(), Error>(())
     /// ```
     pub fn is_connected(&mut self) -> Result<bool, TransferError<SPIErr, PinErr>> {
-        let setup = self.read_register(Register::SETUP_AW)?;
-        if (1..=3).contains(&setup) {
-            Ok(true)
-        } else {
-            Ok(false)
+        let setup_aw = self.read_register(Register::SETUP_AW)?;
+        let mut connected = true;
+        for pattern in [0b01, 0b11].iter() {
+            self.write_register(Register::SETUP_AW, *pattern)?;
+            connected &= self.read_register(Register::SETUP_AW)? == *pattern;
         }
+        self.write_register(Register::SETUP_AW, setup_aw)?;
+        Ok(connected)
     }
 
     /// Opens a reading pipe for reading data on an address.
     ///
+    /// Data pipes 2 to 5 share all but the first byte of the address with data pipe 1, see
+    /// [`set_rx_address()`](#method.set_rx_address).
+    ///
     /// # Examples
-    /// ```rust
-    /// chip.open_reading_pipe(DataPipe::DP0, b"Node1")?;
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.open_reading_pipe(DataPipe::DP1, b"1Node")?;
+    /// chip.open_reading_pipe(DataPipe::DP2, b"2Node")?;
+    /// # Ok::<(), Error>(())
     /// ```
     ///
     /// `pipe` can either be an instance of the type [`DataPipe`] or an integer.
     /// Note that if an integer is provided, numbers higher than 5 will default to reading pipe 0.
     ///
+    /// Returns [`TransferError::InvalidAddressLength`] if the length of `addr` doesn't match the
+    /// configured address width, see [`set_address_width()`](#method.set_address_width). Data
+    /// pipes 2 to 5 also accept a single byte, see [`set_rx_address()`](#method.set_rx_address).
+    ///
     /// # Warnings
     /// You have to call this before calling [`start_listening()`](#method.start_listening).
+    ///
+    /// [`open_writing_pipe()`](#method.open_writing_pipe) overwrites the address of data pipe 0,
+    /// as acknowledgements are received on it. When transmitting as well, prefer the other pipes
+    /// for reading.
     pub fn open_reading_pipe<T: Into<DataPipe>>(
         &mut self,
         pipe: T,
-        mut addr: &[u8],
+        addr: &[u8],
     ) -> Result<(), TransferError<SPIErr, PinErr>> {
         let pipe = pipe.into();
-        if addr.len() > Self::MAX_ADDR_WIDTH {
-            addr = &addr[0..Self::MAX_ADDR_WIDTH];
-        }
-
-        // Get the memory map address corresponding to the data pipe.
-        let rx_address_reg: Register = pipe.into();
-        match pipe {
-            DataPipe::DP0 | DataPipe::DP1 => self.write_register(rx_address_reg, addr)?,
-            _ => self.write_register(rx_address_reg, addr[0])?,
-        }
+        self.set_rx_address(pipe, addr)?;
 
         // Enable corresponding RX Addr
         let old_reg = self.read_register(Register::EN_RXADDR)?; // Read old value
+        if old_reg & (1 << pipe.pipe()) == 0 {
+            // Only the widths of the enabled pipes are kept up to date
+            let width = self.rx_payload_widths[pipe.pipe() as usize];
+            self.write_register(pipe.payload_width_register(), width)?;
+        }
         self.write_register(Register::EN_RXADDR, old_reg | (1 << pipe.pipe()))?; // Update
 
         Ok(())
     }
 
+    /// Opens data pipe 1 on `base_addr`, and data pipes 2 and up on the same address with the
+    /// least significant byte replaced by the bytes in `lsbs`.
+    ///
+    /// Data pipes 2 to 5 share all but the least significant byte with data pipe 1, see
+    /// [`set_rx_address()`](#method.set_rx_address).
+    ///
+    /// Returns [`TransferError::InvalidAddressLength`] if the length of `base_addr` doesn't match
+    /// the configured address width. Returns [`ConfigError::TooManyPipes`] if `lsbs` holds more than
+    /// 4 bytes, and [`ConfigError::DuplicateAddress`] if two pipes would share an address,
+    /// wrapped in [`TransferError::Config`]. Nothing is written in that case.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Listen on "1Node" on pipe 1, "2Node" on pipe 2 and "3Node" on pipe 3
+    /// chip.configure_pipe_group(b"1Node", b"23")?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn configure_pipe_group(
+        &mut self,
+        base_addr: &[u8],
+        lsbs: &[u8],
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        if lsbs.len() > 4 {
+            return Err(TransferError::Config(ConfigError::TooManyPipes(
+                core::cmp::min(lsbs.len(), u8::MAX as usize) as u8,
+            )));
+        }
+        let mut pipes = [(DataPipe::DP1, base_addr); 5];
+        for (i, lsb) in lsbs.iter().enumerate() {
+            pipes[i + 1] = (DataPipe::from(i as u8 + 2), core::slice::from_ref(lsb));
+        }
+        self.configure_receiver(&pipes[..=lsbs.len()])
+    }
+
+    /// Opens every listed data pipe on its address, ready for
+    /// [`start_listening()`](#method.start_listening).
+    ///
+    /// The pipes are opened in the order they are listed, pipes that are not listed are left
+    /// untouched. Data pipes 2 to 5 take all but the least significant byte of their address
+    /// from data pipe 1, see [`set_rx_address()`](#method.set_rx_address).
+    ///
+    /// Returns [`TransferError::InvalidAddressLength`] if the length of an address doesn't
+    /// match the configured address width. Returns [`ConfigError::TooManyPipes`] if more than
+    /// 6 entries are given, and [`ConfigError::DuplicateAddress`] if two of data pipes 1 to 5
+    /// share a least significant byte, wrapped in [`TransferError::Config`]. Nothing is written
+    /// in that case.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.configure_receiver(&[
+    ///     (DataPipe::DP1, b"1Node"),
+    ///     (DataPipe::DP2, b"2Node"),
+    ///     (DataPipe::DP3, b"3"),
+    /// ])?;
+    /// chip.start_listening()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn configure_receiver(
+        &mut self,
+        pipes: &[(DataPipe, &[u8])],
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        if pipes.len() > 6 {
+            return Err(TransferError::Config(ConfigError::TooManyPipes(
+                core::cmp::min(pipes.len(), u8::MAX as usize) as u8,
+            )));
+        }
+        for (i, (pipe, addr)) in pipes.iter().enumerate() {
+            self.check_rx_address_length(*pipe, addr)?;
+            if *pipe == DataPipe::DP0 {
+                continue;
+            }
+            let duplicate = pipes[..i]
+                .iter()
+                .any(|(other, other_addr)| *other != DataPipe::DP0 && other_addr[0] == addr[0]);
+            if duplicate {
+                return Err(TransferError::Config(ConfigError::DuplicateAddress(
+                    addr[0],
+                )));
+            }
+        }
+
+        for (pipe, addr) in pipes {
+            self.open_reading_pipe(*pipe, addr)?;
+        }
+        Ok(())
+    }
+
+    /// Closes a reading pipe, so it no longer receives data.
+    ///
+    /// The address of the pipe is left untouched.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.open_reading_pipe(DataPipe::DP2, b"Node2")?;
+    /// // Stop listening to Node2
+    /// chip.close_reading_pipe(DataPipe::DP2)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn close_reading_pipe(
+        &mut self,
+        pipe: DataPipe,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let old_reg = self.read_register(Register::EN_RXADDR)?;
+        self.write_register(Register::EN_RXADDR, old_reg & !(1 << pipe.pipe()))
+    }
+
     /// Opens a writing pipe for writing data to an address.
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
     /// // Open writing pipe for address "Node1"
     /// chip.open_writing_pipe(b"Node1")?;
+    /// # Ok::<(), Error>(())
     /// ```
+    ///
+    /// Returns [`TransferError::InvalidAddressLength`] if the length of `addr` doesn't match the
+    /// configured address width, see [`set_address_width()`](#method.set_address_width).
+    ///
     /// # Warnings
-    /// Must be called before writing data.
-    pub fn open_writing_pipe(
-        &mut self,
-        mut addr: &[u8],
-    ) -> Result<(), TransferError<SPIErr, PinErr>> {
-        if addr.len() > Self::MAX_ADDR_WIDTH {
-            addr = &addr[0..Self::MAX_ADDR_WIDTH];
-        }
+    /// Must be called before writing data. The chip only transmits after
+    /// [`stop_listening()`](#method.stop_listening), writing while listening returns
+    /// [`TransferError::NotInTxMode`].
+    ///
+    /// The address of data pipe 0 is set to `addr` as well, because acknowledgements are
+    /// received on it. This replaces any address opened for reading on data pipe 0. Use
+    /// [`set_tx_address()`](#method.set_tx_address) to only set the transmit address.
+    pub fn open_writing_pipe(&mut self, addr: &[u8]) -> Result<(), TransferError<SPIErr, PinErr>> {
         // We need to open Reading Pipe 0 with the same address name
         // because ACK messages will be recieved on this channel
-        self.write_register(Register::RX_ADDR_P0, addr)?;
+        self.set_rx_address(DataPipe::DP0, addr)?;
         // Open writing pipe
-        self.write_register(Register::TX_ADDR, addr)?;
+        self.set_tx_address(addr)
+    }
 
-        Ok(())
+    /// Sets the transmit address, without changing the address of data pipe 0.
+    ///
+    /// Returns [`TransferError::InvalidAddressLength`] if the length of `addr` doesn't match the
+    /// configured address width, see [`set_address_width()`](#method.set_address_width).
+    ///
+    /// # Warnings
+    /// With auto acknowledgement, the acknowledgement is sent back to the transmit address and
+    /// received on data pipe 0. If the address of data pipe 0 differs, acknowledgements are
+    /// never received and every payload reaches the maximum number of retries.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Send to "Node1" without acknowledgements, while listening on "Home0" on pipe 0
+    /// chip.set_tx_address(b"Node1")?;
+    /// chip.open_reading_pipe(DataPipe::DP0, b"Home0")?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_tx_address(&mut self, addr: &[u8]) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.check_address_length(addr)?;
+        self.write_register(Register::TX_ADDR, addr)
+    }
+
+    /// Sets the receive address of a data pipe, without enabling the pipe.
+    ///
+    /// Addresses are sent to the chip least significant byte first, so `addr[0]` is the least
+    /// significant byte. Data pipes 0 and 1 take the full address. Data pipes 2 to 5 only have
+    /// their own least significant byte and inherit the other bytes from data pipe 1, so only
+    /// `addr[0]` is used. This way the same full addresses work for data pipes 1 to 5, as long
+    /// as they only differ in the first byte. Data pipes 2 to 5 also accept just that byte.
+    ///
+    /// Returns [`TransferError::InvalidAddressLength`] if the length of `addr` doesn't match the
+    /// configured address width, see [`set_address_width()`](#method.set_address_width).
+    ///
+    /// # Warnings
+    /// The remaining bytes of `addr` are ignored for data pipes 2 to 5. If they don't match the
+    /// address of data pipe 1, the pipe listens on a different address than `addr`.
+    ///
+    /// Changing the address of data pipe 0 on a transmitter breaks the reception of
+    /// acknowledgements, see [`set_tx_address()`](#method.set_tx_address).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.set_rx_address(DataPipe::DP1, b"1Node")?;
+    /// // Listens on "2Node", the "Node" bytes are shared with data pipe 1
+    /// chip.set_rx_address(DataPipe::DP2, b"2Node")?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_rx_address(
+        &mut self,
+        pipe: DataPipe,
+        addr: &[u8],
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.check_rx_address_length(pipe, addr)?;
+
+        // Get the memory map address corresponding to the data pipe.
+        let rx_address_reg: Register = pipe.into();
+        match pipe {
+            DataPipe::DP0 | DataPipe::DP1 => self.write_register(rx_address_reg, addr),
+            _ => self.write_register(rx_address_reg, addr[0]),
+        }
+    }
+
+    /// Returns the full 5 byte address of the writing pipe.
+    ///
+    /// The TX_ADDR register always holds 5 bytes, regardless of the configured address width.
+    /// Wrap the result in an [`Address`](config/struct.Address.html) to print it as hexadecimal.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.open_writing_pipe(b"Node1")?;
+    /// assert_eq!(&chip.tx_address_array()?, b"Node1");
+    /// // Prints the address as hexadecimal
+    /// println!("{:?}", Address::from(chip.tx_address_array()?));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn tx_address_array(&mut self) -> Result<[u8; 5], TransferError<SPIErr, PinErr>> {
+        self.read_address(Register::TX_ADDR)
+    }
+
+    /// Reads the address of the writing pipe into `buf`, and returns the number of bytes read.
+    ///
+    /// The TX_ADDR register holds 5 bytes, `buf` receives as many as fit.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.open_writing_pipe(b"Node1")?;
+    /// let mut addr = [0u8; 5];
+    /// let len = chip.writing_pipe_address(&mut addr)?;
+    /// assert_eq!(&addr[..len], b"Node1");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn writing_pipe_address(
+        &mut self,
+        buf: &mut [u8],
+    ) -> Result<usize, TransferError<SPIErr, PinErr>> {
+        let addr = self.tx_address_array()?;
+        let len = core::cmp::min(buf.len(), addr.len());
+        buf[..len].copy_from_slice(&addr[..len]);
+        Ok(len)
+    }
+
+    /// Reads the address of a reading pipe into `buf`, and returns the number of bytes read.
+    ///
+    /// Pipes 0 and 1 hold a full 5 byte address. Pipes 2 to 5 only hold the least significant
+    /// byte, the other bytes are shared with pipe 1. `buf` receives as many bytes as fit.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.open_reading_pipe(DataPipe::DP1, b"Node1")?;
+    /// let mut addr = [0u8; 5];
+    /// let len = chip.reading_pipe_address(DataPipe::DP1, &mut addr)?;
+    /// assert_eq!(&addr[..len], b"Node1");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn reading_pipe_address(
+        &mut self,
+        pipe: DataPipe,
+        buf: &mut [u8],
+    ) -> Result<usize, TransferError<SPIErr, PinErr>> {
+        let register: Register = pipe.into();
+        let mut addr = [0; 5];
+        let width = match pipe {
+            DataPipe::DP0 | DataPipe::DP1 => {
+                addr = self.read_address(register)?;
+                Self::MAX_ADDR_WIDTH
+            }
+            _ => {
+                addr[0] = self.read_register(register)?;
+                1
+            }
+        };
+        let len = core::cmp::min(buf.len(), width);
+        buf[..len].copy_from_slice(&addr[..len]);
+        Ok(len)
     }
 
     /// Starts listening on the pipes that are opened for reading.
     /// Used in Receiver Mode.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
     /// // First open data pipe 0 with address "Node1"
     /// chip.open_reading_pipe(DataPipe::DP0, b"Node1")?;
     /// // Configure the chip to listening modes (non blocking)
-    /// chip.start_listening()
+    /// chip.start_listening()?;
     /// // Now we can check for available messages and read them
+    /// # Ok::<(), Error>(())
     /// ```
     /// # Warnings
     /// Make sure at least one pipe is opened for reading using the [`open_reading_pipe()`](#method.open_reading_pipe) method.
@@ -253,16 +628,45 @@ where
         Ok(())
     }
 
+    /// Puts the chip back in listening mode without clearing the interrupt flags.
+    ///
+    /// Unlike [`start_listening()`](#method.start_listening), this does not reset the status
+    /// register, so a pending data ready flag from a payload already in the RX FIFO is
+    /// preserved. Useful in tight polling loops that switch in and out of RX mode frequently.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.start_listening()?;
+    /// // ...
+    /// chip.stop_listening()?;
+    /// chip.write(&mut delay, b"ping")?;
+    /// // Continue listening, keeping any received data flagged
+    /// chip.resume_listening()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn resume_listening(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        // Enable RX listening flag
+        self.config_reg |= 1;
+        self.write_register(Register::CONFIG, self.config_reg)?;
+
+        self.set_ce_high()
+    }
+
     /// Stops listening.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
     /// // Configure chip and start listening
     /// chip.open_reading_pipe(DataPipe::DP0, b"Node1")?;
     /// chip.start_listening()?;
     /// // ... read data
     /// // Reading is done, now we can stop listening
     /// chip.stop_listening()?;
+    /// # Ok::<(), Error>(())
     /// ```
     ///
     // TODO: Use the type system to make start and stop listening by RAII and Drop
@@ -275,23 +679,68 @@ where
         Ok(())
     }
 
+    /// Returns `true` if the chip is listening, i.e. it is in receive mode and CE is high.
+    ///
+    /// Based on the state kept by the driver, so no SPI transaction is made.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.start_listening()?;
+    /// assert!(chip.is_listening());
+    /// chip.stop_listening()?;
+    /// assert!(!chip.is_listening());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn is_listening(&self) -> bool {
+        self.config_reg & 1 != 0 && self.ce_high
+    }
+
+    /// Sets the role of the chip, receiver if `rx` is `true` and transmitter otherwise.
+    ///
+    /// Only changes the `PRIM_RX` bit in the CONFIG register and leaves CE untouched,
+    /// unlike [`start_listening()`](#method.start_listening) and
+    /// [`stop_listening()`](#method.stop_listening), which also drive CE.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Prepare for receiving, CE is driven elsewhere
+    /// chip.set_role(true)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_role(&mut self, rx: bool) -> Result<(), TransferError<SPIErr, PinErr>> {
+        if rx {
+            self.config_reg |= 1;
+        } else {
+            self.config_reg &= !0b1;
+        }
+        self.write_register(Register::CONFIG, self.config_reg)
+    }
+
     /// Checks if there are any bytes available to be read.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
     /// // Chip has to be set in listening mode first
     /// chip.open_reading_pipe(DataPipe::DP0, b"Node1")?;
     /// chip.start_listening()?;
     /// // Check if there is any data to read
     /// while chip.data_available()? {
     ///     // ... read the payload
-    ///     delay.delay_ms(50); // small delay between calls of data_available
+    ///     delay.delay_ms(50u8); // small delay between calls of data_available
     /// }
+    /// # Ok::<(), Error>(())
     /// ```
     ///
     /// # Notes
     /// If data_available is called in too rapid succession, the chip can glitch out.
     /// If this is the case, just add a small delay between calling successive `data_available`.
+    /// A glitched status read is reported as [`TransferError::InvalidStatus`].
     pub fn data_available(&mut self) -> Result<bool, TransferError<SPIErr, PinErr>> {
         Ok(self.data_available_on_pipe()?.is_some())
     }
@@ -299,7 +748,9 @@ where
     /// Returns the data pipe where the data is available and `None` if no data available.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
     /// // Chip has to be set in listening mode first
     /// chip.open_reading_pipe(DataPipe::DP0, b"Node1")?;
     /// chip.start_listening()?;
@@ -307,13 +758,17 @@ where
     /// while let Some(pipe) = chip.data_available_on_pipe()? {
     ///     if pipe == DataPipe::DP1 {
     ///         // ... read the payload
-    ///         delay.delay_ms(50); // small delay between calls of data_available
+    ///         delay.delay_ms(50u8); // small delay between calls of data_available
     ///     }
     /// }
+    /// # Ok::<(), Error>(())
     /// ```
     pub fn data_available_on_pipe(
         &mut self,
     ) -> Result<Option<DataPipe>, TransferError<SPIErr, PinErr>> {
+        if let Some((status, _)) = self.peeked {
+            return Ok(status.data_pipe_available());
+        }
         Ok(self.status()?.data_pipe_available())
     }
 
@@ -325,14 +780,23 @@ where
     /// * [`open_reading_pipe()`](#method.open_reading_pipe)
     /// * [`start_listening()`](#method.start_listening)
     ///
-    /// Returns the number of bytes read into the buffer.
+    /// Returns the number of bytes read into the buffer. The bytes of `buf` past the payload are
+    /// left untouched.
+    /// Returns [`TransferError::BufferTooSmall`] if the payload doesn't fit in `buf`, the payload
+    /// is left in the RX FIFO so it can be read again with a larger buffer.
+    ///
+    /// With dynamic payloads, only the width of the payload is read, see
+    /// [`dynamic_payload_length()`](#method.dynamic_payload_length). If the width is corrupt,
+    /// the RX FIFO is flushed and [`TransferError::CorruptPayload`] is returned.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
     /// // We will be receiving float values
     /// // Set the payload size to 4 bytes, the size of an f32
     /// let config = NrfConfig::default().payload_size(PayloadSize::Static(4));
-    /// let chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config).unwrap();
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config).unwrap();
     /// // Put the chip in listening mode
     /// chip.open_reading_pipe(DataPipe::DP0, b"Node1");
     /// chip.start_listening();
@@ -356,37 +820,237 @@ where
     ///     // Wait some time before trying again
     ///     delay.delay_us(50u16);
     /// }
+    /// # Ok::<(), Error>(())
     /// ```
     pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, TransferError<SPIErr, PinErr>> {
-        let len = if let PayloadSize::Static(n) = self.payload_size {
-            n as usize
-        } else {
-            core::cmp::min(buf.len(), MAX_PAYLOAD_SIZE as usize)
-        };
+        self.read_payload(buf).map(|(_, len)| len)
+    }
 
-        // Use tx buffer to copy the values into
-        // First byte will be the opcode
-        self.tx_buf[0] = Instruction::RRX.opcode();
-        // Write to spi
+    /// Waits until a payload is available and reads it, see [`read()`](#method.read).
+    ///
+    /// Polls for data every millisecond, which keeps the polls far enough apart, see
+    /// [`data_available()`](#method.data_available). Returns [`TransferError::Timeout`] if no
+    /// payload arrived within `timeout_ms` milliseconds.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # fn handle(_: &[u8]) {}
+    /// chip.open_reading_pipe(DataPipe::DP0, b"Node1")?;
+    /// chip.start_listening()?;
+    /// let mut buffer = [0u8; 32];
+    /// match chip.read_blocking(&mut delay, &mut buffer, 1000) {
+    ///     Ok(n) => handle(&buffer[..n]),
+    ///     Err(TransferError::Timeout) => {} // Nothing received within a second
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn read_blocking<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        buf: &mut [u8],
+        timeout_ms: u32,
+    ) -> Result<usize, TransferError<SPIErr, PinErr>> {
+        let mut waited = 0;
+        loop {
+            if self.data_available()? {
+                return self.read(buf);
+            }
+            if waited >= timeout_ms {
+                return Err(TransferError::Timeout);
+            }
+            delay.delay_ms(Self::RX_POLL_INTERVAL_MS);
+            waited += Self::RX_POLL_INTERVAL_MS as u32;
+        }
+    }
+
+    /// Reads the available payload, and returns the data pipe it was received on together with
+    /// the number of bytes read into the buffer.
+    ///
+    /// The data pipe is taken from the status that is clocked out while the payload is read,
+    /// so it always belongs to the payload, even if more payloads arrive in the meantime.
+    /// Returns `None` if the RX FIFO was empty.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # fn handle_node1(_: &[u8]) {}
+    /// # fn handle_node2(_: &[u8]) {}
+    /// let mut buffer = [0u8; 32];
+    /// while let Some((pipe, len)) = chip.read_with_pipe(&mut buffer)? {
+    ///     match pipe {
+    ///         DataPipe::DP1 => handle_node1(&buffer[..len]),
+    ///         DataPipe::DP2 => handle_node2(&buffer[..len]),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn read_with_pipe(
+        &mut self,
+        buf: &mut [u8],
+    ) -> Result<Option<(DataPipe, usize)>, TransferError<SPIErr, PinErr>> {
+        let (status, len) = self.read_payload(buf)?;
+        Ok(status.data_pipe_available().map(|pipe| (pipe, len)))
+    }
+
+    /// Reads payloads until the RX FIFO is empty, and calls `f` with the data pipe and the data
+    /// of every payload. Returns the number of payloads read.
+    ///
+    /// Interrupts can coalesce, so a single data ready interrupt can mean up to three payloads
+    /// are waiting. Draining the FIFO makes sure none of them are left behind until the next
+    /// payload arrives.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # fn handle(_: DataPipe, _: &[u8]) {}
+    /// // On a data ready interrupt
+    /// chip.read_all(|pipe, data| {
+    ///     handle(pipe, data);
+    /// })?;
+    /// chip.reset_status()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn read_all<F>(&mut self, mut f: F) -> Result<usize, TransferError<SPIErr, PinErr>>
+    where
+        F: FnMut(DataPipe, &[u8]),
+    {
+        let mut count = 0;
+        let mut buf = [0; MAX_PAYLOAD_SIZE as usize];
+        let len = self.max_rx_payload_len();
+        while self.peeked.is_some() || !self.fifo_status()?.rx_empty() {
+            match self.read_with_pipe(&mut buf[..len])? {
+                Some((pipe, n)) => f(pipe, &buf[..n]),
+                None => break,
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Copies the first `n` bytes of the available payload into `out`, without consuming the
+    /// payload. Returns the number of bytes copied, which is smaller than `n` if the payload or
+    /// `out` is shorter. Returns 0 if no payload is available.
+    ///
+    /// The chip can't read a payload without removing it from the RX FIFO, so the whole payload
+    /// is read and kept by the driver. The next [`read()`](#method.read),
+    /// [`read_with_pipe()`](#method.read_with_pipe) or [`read_all()`](#method.read_all) returns
+    /// the kept payload, and [`data_available()`](#method.data_available) reports it as
+    /// available. Peeking again returns the same payload.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # const MSG_TEMPERATURE: u8 = 1;
+    /// # let (mut temperature, mut other) = ([0u8; 5], [0u8; 32]);
+    /// let mut header = [0u8; 1];
+    /// if chip.peek_header(1, &mut header)? == 1 {
+    ///     match header[0] {
+    ///         MSG_TEMPERATURE => chip.read(&mut temperature)?,
+    ///         _ => chip.read(&mut other)?,
+    ///     };
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    ///
+    /// # Notes
+    /// [`flush_rx()`](#method.flush_rx) discards the kept payload as well.
+    pub fn peek_header(
+        &mut self,
+        n: usize,
+        out: &mut [u8],
+    ) -> Result<usize, TransferError<SPIErr, PinErr>> {
+        if self.peeked.is_none() {
+            let mut buf = [0; MAX_PAYLOAD_SIZE as usize];
+            let max = self.max_rx_payload_len();
+            let (status, len) = self.read_payload(&mut buf[..max])?;
+            if status.data_pipe_available().is_none() {
+                // The RX FIFO was empty
+                return Ok(0);
+            }
+            self.peek_buf = buf;
+            self.peeked = Some((status, len as u8));
+        }
+        let len = self.peeked.map_or(0, |(_, len)| len as usize);
+        let n = core::cmp::min(core::cmp::min(n, len), out.len());
+        out[..n].copy_from_slice(&self.peek_buf[..n]);
+        Ok(n)
+    }
+
+    /// Returns the width of the payload at the top of the RX FIFO.
+    ///
+    /// Only meaningful when dynamic payloads are enabled.
+    ///
+    /// A width greater than [`MAX_PAYLOAD_SIZE`](constant.MAX_PAYLOAD_SIZE.html) means the RX FIFO
+    /// is corrupt. In that case the RX FIFO is flushed and [`TransferError::CorruptPayload`] is
+    /// returned.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.set_payload_size(PayloadSize::Dynamic)?;
+    /// // ...
+    /// if chip.data_available()? {
+    ///     let len = chip.dynamic_payload_length()?;
+    ///     let mut buffer = [0u8; 32];
+    ///     chip.read(&mut buffer[..len as usize])?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn dynamic_payload_length(&mut self) -> Result<u8, TransferError<SPIErr, PinErr>> {
+        self.tx_buf[..2].copy_from_slice(&[Instruction::RRXPLWID.opcode(), 0]);
         self.set_ncs_low()?;
-        let r = self.spi_transfer_tx_buf(len)?;
-        // Transfer the data read to buf.
-        // Skip first byte because it contains the command.
-        // Make both slices are the same length, otherwise `copy_from_slice` panics.
-        buf[..len].copy_from_slice(&r[1..=len]);
+        let width = self.spi_transfer_tx_buf(1)?[1];
         self.set_ncs_high()?;
 
-        Ok(len)
+        if width > MAX_PAYLOAD_SIZE {
+            self.flush_rx()?;
+            return Err(TransferError::CorruptPayload);
+        }
+        Ok(width)
+    }
+
+    /// Checks the width of the payload at the top of the RX FIFO.
+    ///
+    /// Returns `None` if the width is corrupt, in which case the RX FIFO has been flushed.
+    /// In dynamic payload mode, a corrupt width can lock up the RX FIFO and silently stop
+    /// reception, so this can be called periodically to detect and clear that state.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// if chip.rx_width_valid()?.is_none() {
+    ///     // The RX FIFO was flushed, the payload is lost
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn rx_width_valid(&mut self) -> Result<Option<u8>, TransferError<SPIErr, PinErr>> {
+        match self.dynamic_payload_length() {
+            Ok(width) => Ok(Some(width)),
+            Err(TransferError::CorruptPayload) => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 
     /// Writes data to the opened channel.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// # fn get_reading() -> f32 { 0.0 }
     /// // We will be sending float values
     /// // Set the payload size to 4 bytes, the size of an f32
     /// let config = NrfConfig::default().payload_size(PayloadSize::Static(4));
-    /// let chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config).unwrap();
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config).unwrap();
     /// // Put the chip in transmission mode
     /// chip.open_writing_pipe(b"Node1");
     /// chip.stop_listening();
@@ -407,46 +1071,35 @@ where
     ///     // Wait some time before trying again
     ///     delay.delay_us(50u16);
     /// }
+    /// # Ok::<(), Error>(())
     /// ```
     ///
     /// Will clear all interrupt flags after write.
-    /// Returns an error when max retries have been reached.
+    /// Returns an error when max retries have been reached by the previous payload.
+    /// Returns [`TransferError::BufferTooLarge`] if `buf` doesn't fit in a single payload, see
+    /// [`payload_size()`](#method.payload_size).
+    /// Returns [`TransferError::TxFifoFull`] if the payload was dropped because the
+    /// transmission queue was full, see [`can_write()`](#method.can_write).
+    /// Returns [`TransferError::NotInTxMode`] if the chip is listening, see
+    /// [`stop_listening()`](#method.stop_listening). The same goes for the other methods that
+    /// transmit.
+    ///
+    /// Doesn't return a [`WriteOutcome`]: it returns right after starting the transmission, when
+    /// neither the acknowledgement nor the maximum number of retries can have happened yet, and
+    /// the status flags it reads still belong to the previous payload. Waiting for the outcome
+    /// here would also stop the TX FIFO from being kept filled, like
+    /// [`write_all()`](#method.write_all) does. Use [`write_timeout()`](#method.write_timeout) to
+    /// wait for the outcome of this payload, it tells an acknowledged payload apart from one
+    /// that was sent without expecting an acknowledgement.
     pub fn write<D: DelayUs<u8>>(
         &mut self,
         delay: &mut D,
         buf: &[u8],
     ) -> Result<(), TransferError<SPIErr, PinErr>> {
-        let send_count = if let PayloadSize::Static(n) = self.payload_size {
-            let n = n as usize;
-            // we have to send `n` bytes
-            let len = core::cmp::min(buf.len(), n);
-            self.tx_buf[1..=len].copy_from_slice(&buf[..len]);
-            if len < MAX_PAYLOAD_SIZE as usize {
-                self.tx_buf[len + 1..=n].fill(0);
-            }
-            // now our tx_buf is guarantueed to have `n` bytes filled
-            n
-        } else {
-            // In dynamic payload mode, max payload_size is the limit
-            core::cmp::min(buf.len(), MAX_PAYLOAD_SIZE as usize)
-        };
-
-        // Add instruction to buffer
-        self.tx_buf[0] = Instruction::WTX.opcode();
-        // Write to spi
-        self.set_ncs_low()?;
-        let r = self.spi_transfer_tx_buf(send_count)?;
-        let status = Status::from(r[0]);
-        self.set_ncs_high()?;
-
-        // Start transmission:
-        // pulse CE pin to signal transmission start
-        self.set_ce_high()?;
-        delay.delay_us(10);
-        self.set_ce_low()?;
+        let status = self.start_transmission(delay, buf)?;
 
         // Clear interrupt flags
-        self.write_register(Register::STATUS, Status::flags().value())?;
+        self.reset_status()?;
 
         // Max retries exceeded
         if status.reached_max_retries() {
@@ -457,22 +1110,509 @@ where
         Ok(())
     }
 
-    /// Setup of automatic retransmission.
+    /// Writes a fixed size payload to the opened channel, see [`write()`](#method.write).
+    ///
+    /// Returns [`TransferError::PayloadSizeMismatch`] if a static payload size is configured and
+    /// the length of the payload is different, or not 2 bytes less with the software CRC, see
+    /// [`set_software_crc()`](#method.set_software_crc). With dynamic payloads any payload that fits is
+    /// sent, and [`TransferError::BufferTooLarge`] is returned otherwise.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// # fn get_reading() -> f32 { 0.0 }
+    /// let config = NrfConfig::default().payload_size(PayloadSize::Static(4));
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config)?;
+    /// chip.open_writing_pipe(b"Node1")?;
+    /// chip.stop_listening()?;
+    ///
+    /// let payload = Payload::new(get_reading().to_le_bytes());
+    /// chip.write_payload(&mut delay, &payload)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    ///
+    /// Only available with the `payload` feature.
+    #[cfg(feature = "payload")]
+    pub fn write_payload<D: DelayUs<u8>, const N: usize>(
+        &mut self,
+        delay: &mut D,
+        payload: &Payload<N>,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        if let PayloadSize::Static(_) = self.payload_size {
+            let expected = self.max_payload_len();
+            if expected != N {
+                return Err(TransferError::PayloadSizeMismatch {
+                    expected: expected as u8,
+                    got: core::cmp::min(N, u8::MAX as usize) as u8,
+                });
+            }
+        }
+        self.write(delay, payload.as_bytes())
+    }
+
+    /// Writes data to the opened channel and waits until it has been sent.
+    ///
+    /// Unlike [`write()`](#method.write), which returns right after starting the transmission,
+    /// this polls the status register until the payload was sent, and acknowledged if auto
+    /// acknowledgement is enabled, or the maximum number of retries was reached.
+    ///
+    /// Will clear all interrupt flags after write.
+    /// Returns a [`WriteOutcome`], which tells an acknowledged payload apart from a payload that
+    /// was sent without expecting an acknowledgement, because auto acknowledgement is disabled
+    /// on data pipe 0.
+    /// Returns [`TransferError::MaximumRetries`] if the maximum number of retries was reached,
+    /// the payload is flushed from the TX FIFO.
+    /// Returns [`TransferError::Timeout`] if neither happened within `timeout_us` microseconds.
+    /// The payload is left in the TX FIFO, use [`flush_tx()`](#method.flush_tx) to drop it.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.open_writing_pipe(b"Node1")?;
+    /// chip.stop_listening()?;
+    /// // 15 retries with a 4000µs delay take at most about 60ms
+    /// match chip.write_timeout(&mut delay, b"Hello", 70_000) {
+    ///     Ok(WriteOutcome::Acknowledged) => {} // Delivered
+    ///     Ok(WriteOutcome::SentNoAck) => {} // Sent, delivery unknown
+    ///     Err(TransferError::MaximumRetries) => {} // Not acknowledged
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn write_timeout<D: DelayUs<u8>>(
+        &mut self,
+        delay: &mut D,
+        buf: &[u8],
+        timeout_us: u32,
+    ) -> Result<WriteOutcome, TransferError<SPIErr, PinErr>> {
+        self.start_transmission(delay, buf)?;
+        // Round up, so the timeout is never cut short
+        let interval = Self::TX_POLL_INTERVAL_US as u32;
+        let polls = timeout_us / interval + (timeout_us % interval != 0) as u32;
+        let status = match self.wait_for_transmission(delay, polls.max(1))? {
+            Some(status) => status,
+            None => return Err(TransferError::Timeout),
+        };
+        // The outcome is known, so the retransmissions are final
+        self.account_retransmissions()?;
+        self.reset_status()?;
+        if status.reached_max_retries() {
+            self.flush_tx()?;
+            return Err(TransferError::MaximumRetries);
+        }
+        // Acknowledgements are received on data pipe 0
+        if self.read_register(Register::EN_AA)? & 1 != 0 {
+            Ok(WriteOutcome::Acknowledged)
+        } else {
+            Ok(WriteOutcome::SentNoAck)
+        }
+    }
+
+    /// Writes data to the opened channel, and resends it when the maximum number of retries is
+    /// reached, up to `attempts` times in total.
+    ///
+    /// Each attempt waits for the outcome of the transmission, including the automatic
+    /// retransmissions of the chip. Between attempts the interrupt flags and the lost packets
+    /// counter are reset. Returns the number of attempts that were needed.
+    ///
+    /// Returns [`TransferError::MaximumRetries`] if none of the attempts succeeded, the payload
+    /// is flushed from the TX FIFO.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// match chip.write_retry(&mut delay, b"Hello", 3) {
+    ///     Ok(1) => {} // First time right
+    ///     Ok(n) => {} // Recovered after n attempts
+    ///     Err(TransferError::MaximumRetries) => {} // The link is down
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn write_retry<D: DelayUs<u8>>(
+        &mut self,
+        delay: &mut D,
+        buf: &[u8],
+        attempts: u8,
+    ) -> Result<u8, TransferError<SPIErr, PinErr>> {
+        for attempt in 1..=attempts.max(1) {
+            if attempt > 1 {
+                self.reset_packet_loss()?;
+            }
+            if self.transmit_acked(delay, buf)? {
+                return Ok(attempt);
+            }
+        }
+        Err(TransferError::MaximumRetries)
+    }
+
+    /// Writes data to the opened channel without requesting an acknowledgement.
+    ///
+    /// The chip does not wait for an acknowledgement and does not retransmit the payload, so
+    /// this never returns [`TransferError::MaximumRetries`]. Useful for broadcasting to many
+    /// receivers where delivery of individual payloads doesn't matter.
+    ///
+    /// Requires the dynamic acknowledgement feature, see
+    /// [`NrfConfig::dynamic_ack()`](config/struct.NrfConfig.html#method.dynamic_ack) and
+    /// [`enable_dynamic_ack()`](#method.enable_dynamic_ack). Without it, the payload is sent
+    /// as a regular payload that requests an acknowledgement.
+    ///
+    /// Will clear all interrupt flags after write.
+    /// Returns [`TransferError::TxFifoFull`] if the transmission queue is full.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// # let reading = 0.0f32;
+    /// let config = NrfConfig::default().dynamic_ack(true);
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config)?;
+    /// chip.open_writing_pipe(b"Bcast")?;
+    /// chip.stop_listening()?;
+    /// chip.write_no_ack(&mut delay, &reading.to_le_bytes())?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn write_no_ack<D: DelayUs<u8>>(
+        &mut self,
+        delay: &mut D,
+        buf: &[u8],
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.check_tx_mode()?;
+        // The retransmit count of a previous packet resets when this one starts
+        self.account_retransmissions()?;
+        // The payload is dropped when the TX FIFO was full
+        if self.load_payload(Instruction::WTXNOACK, buf)?.tx_full() {
+            return Err(TransferError::TxFifoFull);
+        }
+        self.pulse_ce(delay)?;
+
+        // Clear interrupt flags
+        self.reset_status()
+    }
+
+    /// Loads a payload into the transmission queue and returns immediately.
+    ///
+    /// CE is held high, so the chip keeps transmitting until the queue is empty. This allows
+    /// several payloads to be queued without waiting for each one to be sent.
+    /// Use [`tx_done()`](#method.tx_done) to poll for completion, and
+    /// [`stop_listening()`](#method.stop_listening) to bring CE low again.
+    ///
+    /// Returns [`TransferError::TxFifoFull`] if the transmission queue is full.
+    /// Interrupt flags are not cleared, when the maximum number of retries is reached the
+    /// queue stalls until the flags are reset with [`reset_status()`](#method.reset_status).
+    /// Payloads sent this way are not part of the [`retry_stats()`](#method.retry_stats).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # let readings = [[0u8; 4]; 8];
+    /// chip.open_writing_pipe(b"Node1")?;
+    /// chip.stop_listening()?;
+    /// for reading in readings.iter() {
+    ///     while let Err(TransferError::TxFifoFull) = chip.write_fast(reading) {}
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn write_fast(&mut self, buf: &[u8]) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.check_tx_mode()?;
+        if !self.can_write()? {
+            return Err(TransferError::TxFifoFull);
+        }
+        self.load_payload(Instruction::WTX, buf)?;
+        self.set_ce_high()
+    }
+
+    /// Returns `true` if the transmission queue has room for another payload.
+    ///
+    /// Writing to a full queue drops the payload, so this can be used to hold back new
+    /// payloads until there is room.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # let mut producer = core::iter::repeat([0u8; 4]);
+    /// if chip.can_write()? {
+    ///     chip.write_fast(&producer.next().unwrap())?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn can_write(&mut self) -> Result<bool, TransferError<SPIErr, PinErr>> {
+        Ok(!self.status()?.tx_full())
+    }
+
+    /// Returns `true` if the data sent flag is set.
+    ///
+    /// The flag is not cleared, see [`take_tx_sent()`](#method.take_tx_sent) to also clear it.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.write_fast(b"Hello")?;
+    /// while !chip.tx_done()? {}
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn tx_done(&mut self) -> Result<bool, TransferError<SPIErr, PinErr>> {
+        Ok(self.status()?.data_sent())
+    }
+
+    /// Writes all of `data` to the opened channel, split into payload sized chunks.
+    ///
+    /// Up to three payloads are loaded into the TX FIFO before CE is raised, and CE is held high
+    /// while every payload that gets sent is replaced by the next chunk. Stops at the first
+    /// payload that reaches the maximum number of retries, and flushes the TX FIFO.
+    ///
+    /// Returns the number of bytes that were acknowledged. The TX FIFO should be empty when
+    /// calling this.
+    /// Returns [`TransferError::BufferTooLarge`] if the payload size leaves no room for data.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # let file_contents = [0u8; 1024];
+    /// chip.open_writing_pipe(b"Node1")?;
+    /// chip.stop_listening()?;
+    /// let sent = chip.write_all(&mut delay, &file_contents)?;
+    /// if sent < file_contents.len() {
+    ///     // Resume from `sent` later
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn write_all<D: DelayUs<u8>>(
+        &mut self,
+        delay: &mut D,
+        data: &[u8],
+    ) -> Result<usize, TransferError<SPIErr, PinErr>> {
+        self.check_tx_mode()?;
+        if data.is_empty() {
+            return Ok(0);
+        }
+        let chunk_size = self.max_payload_len();
+        if chunk_size == 0 {
+            return Err(TransferError::BufferTooLarge { max: 0 });
+        }
+        let mut chunks = data.chunks(chunk_size);
+        let total = chunks.len();
+        let mut acked = 0;
+        // The previous packet is done, account for its retransmissions before ARC_CNT resets
+        self.account_retransmissions()?;
+        // Fill the TX FIFO before starting the transmission
+        for chunk in chunks.by_ref().take(Self::TX_FIFO_DEPTH) {
+            self.load_payload(Instruction::WTX, chunk)?;
+        }
+        self.set_ce_high()?;
+        while acked < total {
+            // A packet takes longer than a poll interval, so no TX_DS flag is missed
+            let status = self.wait_for_transmission(delay, Self::TX_POLL_COUNT)?;
+            self.tx_packets = self.tx_packets.wrapping_add(1);
+            self.tx_pending = true;
+            self.account_retransmissions()?;
+            if let Some(status) = status {
+                // Only clear the flags that were read, a packet that finished in the meantime
+                // keeps its TX_DS flag for the next poll
+                self.clear_interrupts(Interrupts::from(status.value()))?;
+            }
+            match status {
+                Some(status) if status.data_sent() => {
+                    acked += 1;
+                    // Refill the slot of the payload that was sent
+                    if let Some(chunk) = chunks.next() {
+                        self.load_payload(Instruction::WTX, chunk)?;
+                    }
+                }
+                _ => {
+                    self.set_ce_low()?;
+                    self.flush_tx()?;
+                    break;
+                }
+            }
+        }
+        self.set_ce_low()?;
+        Ok(core::cmp::min(acked * chunk_size, data.len()))
+    }
+
+    /// Loads a payload that will be sent back along with the acknowledgement of the next packet
+    /// received on `pipe`.
+    ///
+    /// Used in RX mode, so the receiver can reply without switching to TX mode.
+    ///
+    /// Returns [`TransferError::BufferTooLarge`] if `buf` is longer than
+    /// [`MAX_PAYLOAD_SIZE`](constant.MAX_PAYLOAD_SIZE.html), nothing is loaded in that case.
+    /// Returns [`TransferError::AckPayloadsDisabled`] if acknowledgement payloads are not enabled,
+    /// see [`NrfConfig::ack_payloads_enabled()`](config/struct.NrfConfig.html#method.ack_payloads_enabled).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.open_reading_pipe(DataPipe::DP1, b"Node1")?;
+    /// chip.start_listening()?;
+    /// // Reply with "pong" on the next packet received on pipe 1
+    /// chip.write_ack_payload(DataPipe::DP1, b"pong")?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn write_ack_payload(
+        &mut self,
+        pipe: DataPipe,
+        buf: &[u8],
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        if !self.ack_payload_enabled()? {
+            return Err(TransferError::AckPayloadsDisabled);
+        }
+        if buf.len() > MAX_PAYLOAD_SIZE as usize {
+            return Err(TransferError::BufferTooLarge {
+                max: MAX_PAYLOAD_SIZE,
+            });
+        }
+        let len = buf.len();
+        self.tx_buf[0] = Instruction::WACKPL.opcode() | pipe.pipe();
+        self.tx_buf[1..=len].copy_from_slice(&buf[..len]);
+        self.set_ncs_low()?;
+        self.spi_write_tx_buf(len)?;
+        self.set_ncs_high()
+    }
+
+    /// Writes data to the given address.
+    ///
+    /// Opens the writing pipe on `addr` (see [`open_writing_pipe()`](#method.open_writing_pipe))
+    /// and then sends the data like [`write()`](#method.write).
+    /// Useful for a node that talks to a different peer every cycle.
+    ///
+    /// Returns an error if the length of `addr` differs from the configured address width.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// for addr in [b"Node1", b"Node2", b"Node3"].iter() {
+    ///     chip.write_to(&mut delay, *addr, b"ping")?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn write_to<D: DelayUs<u8>>(
+        &mut self,
+        delay: &mut D,
+        addr: &[u8],
+        buf: &[u8],
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.open_writing_pipe(addr)?;
+        self.write(delay, buf)
+    }
+
+    /// Tries to send `test_payload` at every data rate, from fast to slow, and records whether
+    /// it was acknowledged by the receiver.
+    ///
+    /// The original RF setup is restored afterwards, also when a probe returns an error.
+    /// Make sure the chip is in transmission mode and a writing pipe is opened, and that the
+    /// receiver switches data rates in step, otherwise no rate will be acknowledged.
+    /// The 250 kbps data rate is only supported by the nRF24L01+, see
+    /// [`chip_variant()`](#method.chip_variant).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.open_writing_pipe(b"Node1")?;
+    /// let results = chip.probe_data_rates(&mut delay, b"ping")?;
+    /// // Pick the fastest data rate that was acknowledged
+    /// let best = results.iter().find(|(_, acked)| *acked).map(|(rate, _)| *rate);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn probe_data_rates<D: DelayUs<u8>>(
+        &mut self,
+        delay: &mut D,
+        test_payload: &[u8],
+    ) -> Result<[(DataRate, bool); 3], TransferError<SPIErr, PinErr>> {
+        let rf_setup = self.read_register(Register::RF_SETUP)?;
+        let mut results = [
+            (DataRate::R2Mbps, false),
+            (DataRate::R1Mbps, false),
+            (DataRate::R250kbps, false),
+        ];
+        let probed = (|| {
+            for (rate, acked) in results.iter_mut() {
+                self.setup_rf(*rate, PALevel::from(rf_setup))?;
+                *acked = self.transmit_acked(delay, test_payload)?;
+            }
+            Ok(())
+        })();
+        // Restore the original setup, also when a probe failed
+        self.write_register(Register::RF_SETUP, rf_setup)?;
+        self.data_rate = DataRate::from(rf_setup);
+        probed.map(|_| results)
+    }
+
+    /// Sends `count` packets and returns the percentage that was acknowledged.
+    ///
+    /// Each packet is sent with auto acknowledgement and waits for the outcome. Packets that
+    /// reach the maximum number of retries are flushed before the next attempt.
+    /// Auto acknowledgement is enabled on data pipe 0 for the duration of the measurement, and
+    /// the EN_AA register is restored afterwards.
+    /// Returns 0 if `count` is 0.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.open_writing_pipe(b"Node1")?;
+    /// let success_rate = chip.measure_success_rate(&mut delay, 100, b"ping")?;
+    /// if success_rate < 90 {
+    ///     // Consider moving the antenna
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn measure_success_rate<D: DelayUs<u8>>(
+        &mut self,
+        delay: &mut D,
+        count: u16,
+        payload: &[u8],
+    ) -> Result<u8, TransferError<SPIErr, PinErr>> {
+        if count == 0 {
+            return Ok(0);
+        }
+        // Without auto acknowledgement every packet counts as sent
+        let en_aa = self.read_register(Register::EN_AA)?;
+        if en_aa & 1 == 0 {
+            self.write_register(Register::EN_AA, en_aa | 1)?;
+        }
+        let mut acked: u32 = 0;
+        for _ in 0..count {
+            if self.transmit_acked(delay, payload)? {
+                acked += 1;
+            }
+        }
+        // Restore the original setup
+        if en_aa & 1 == 0 {
+            self.write_register(Register::EN_AA, en_aa)?;
+        }
+        Ok((acked * 100 / count as u32) as u8)
+    }
+
+    /// Setup of automatic retransmission.
     ///
     /// # Arguments
     /// * `delay` is the auto retransmit delay.
-    /// Values can be between 0 and 15.
-    /// The delay before a retransmit is initiated, is calculated according to the following formula:
-    /// > ((**delay** + 1) * 250) + 86 µs
+    ///   Values can be between 0 and 15.
+    ///   The delay before a retransmit is initiated, is calculated according to the following formula:
+    ///   > ((**delay** + 1) * 250) + 86 µs
     ///
     /// * `count` is number of times there will be an auto retransmission.
-    /// Must be a value between 0 and 15.
+    ///   Must be a value between 0 and 15.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
     /// // Set the auto transmit delay to (5 + 1) * 250) + 86 = 1586µs
     /// // and the retransmit count to 15.
-    /// nrf24l01.set_retries((5, 15))?;
+    /// chip.set_retries((5, 15))?;
+    /// # Ok::<(), Error>(())
     /// ```
     pub fn set_retries<T: Into<AutoRetransmission>>(
         &mut self,
@@ -485,158 +1625,843 @@ where
         )
     }
 
+    /// Setup of automatic retransmission, verified by reading back the register.
+    ///
+    /// Same as [`set_retries()`](#method.set_retries), but returns
+    /// [`TransferError::ReadbackMismatch`] if the value read back differs from the value written,
+    /// for example because the SPI write was dropped.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Adapt the retry budget to the link quality
+    /// chip.set_retries_verified((5, 15))?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_retries_verified<T: Into<AutoRetransmission>>(
+        &mut self,
+        auto_retry: T,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let auto_retry = auto_retry.into();
+        let expected = (auto_retry.raw_delay() << 4) | (auto_retry.count());
+        self.write_register(Register::SETUP_RETR, expected)?;
+        let actual = self.read_register(Register::SETUP_RETR)?;
+        if actual != expected {
+            return Err(TransferError::ReadbackMismatch { expected, actual });
+        }
+        Ok(())
+    }
+
     /// Returns the auto retransmission config.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
     /// // Initialize the chip
-    /// let mut chip = Nrf24l01::new(spi_struct, ce_pin, ncs_pin, delay, NrfConfig::default())?;
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, NrfConfig::default())?;
     ///
     /// let retries_config = chip.retries()?;
     /// // Default values for the chip
     /// assert_eq!(retries_config.delay(), 1586);
     /// assert_eq!(retries_config.count(), 15);
+    /// # Ok::<(), Error>(())
     /// ```
     pub fn retries(&mut self) -> Result<AutoRetransmission, TransferError<SPIErr, PinErr>> {
         self.read_register(Register::SETUP_RETR)
             .map(AutoRetransmission::from_register)
     }
 
-    /// Set the frequency channel nRF24L01 operates on.
+    /// Returns how many retransmissions were used for the last packet, together with the
+    /// configured maximum, as `(used, allowed)`.
     ///
-    /// # Arguments
-    ///
-    /// * `channel` number between 0 and 127.
+    /// A value approaching the maximum indicates a degrading link, consider changing the
+    /// channel or lowering the data rate.
     ///
     /// # Examples
-    /// ```rust
-    /// nrf24l01.set_channel(74)?;
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.write(&mut delay, b"Hello")?;
+    /// let (used, allowed) = chip.retry_utilization()?;
+    /// if used * 2 > allowed {
+    ///     // More than half of the retries were needed
+    /// }
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn set_channel(&mut self, channel: u8) -> Result<(), TransferError<SPIErr, PinErr>> {
-        self.write_register(Register::RF_CH, (u8::MAX >> 1) & channel)
+    pub fn retry_utilization(&mut self) -> Result<(u8, u8), TransferError<SPIErr, PinErr>> {
+        let used = self.observe_tx()?.retransmits();
+        let allowed = self.retries()?.count();
+        Ok((used, allowed))
     }
 
-    /// Return the frequency channel nRF24L01 operates on.
-    /// Note that the actual frequency will we the channel +2400 MHz.
+    /// Reads the transmit observe register, which holds the lost packets and the
+    /// retransmissions of the last packet. See [`ObserveTx`].
     ///
     /// # Examples
-    /// ```rust
-    /// // Initialize the chip
-    /// let mut chip = Nrf24l01::new(spi_struct, ce_pin, ncs_pin, delay, NrfConfig::default())?;
-    /// // Default is channel 76
-    /// assert_eq!(chip.channel()?, 76);
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.write(&mut delay, b"Hello")?;
+    /// let observe = chip.observe_tx()?;
+    /// let (lost, retransmits) = (observe.lost_packets(), observe.retransmits());
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn channel(&mut self) -> Result<u8, TransferError<SPIErr, PinErr>> {
-        self.read_register(Register::RF_CH)
+    pub fn observe_tx(&mut self) -> Result<ObserveTx, TransferError<SPIErr, PinErr>> {
+        self.read_register(Register::OBSERVE_TX)
+            .map(ObserveTx::from)
     }
 
-    /// Set the address width, saturating values above or below allowed range.
+    /// Returns a snapshot of the link quality, see [`LinkQuality`].
     ///
-    /// # Arguments
-    ///
-    /// * `width` number between 3 and 5.
+    /// Reads the transmit observe register, the received power detector and the power
+    /// amplifier level in one go. Useful for periodic diagnostics.
     ///
     /// # Examples
-    /// ```rust
-    /// nrf24l01.set_address_width(5)?;
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # fn log(_: u8, _: u8, _: bool) {}
+    /// let quality = chip.link_quality()?;
+    /// log(quality.lost_packets(), quality.retransmits(), quality.carrier_detected());
+    /// chip.reset_packet_loss()?;
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn set_address_width<T>(&mut self, width: T) -> Result<(), TransferError<SPIErr, PinErr>>
-    where
-        T: Into<AddressWidth>,
-    {
-        let width = width.into();
-        self.write_register(Register::SETUP_AW, width.value())
+    pub fn link_quality(&mut self) -> Result<LinkQuality, TransferError<SPIErr, PinErr>> {
+        Ok(LinkQuality {
+            observe_tx: self.observe_tx()?,
+            carrier: self.received_power_detector()?,
+            pa_level: self.power_amp_level()?,
+        })
     }
 
-    /// Returns the current data rate as a [`DataRate`] enum.
+    /// Resets the lost packets counter, see [`ObserveTx::lost_packets()`].
+    ///
+    /// The counter saturates at 15, and is reset by rewriting the current channel.
     ///
     /// # Examples
-    /// ```rust
-    /// // Initialize the chip
-    /// let mut chip = Nrf24l01::new(spi_struct, ce_pin, ncs_pin, delay, NrfConfig::default())?;
-    /// // Default is 2 Mb/s
-    /// assert_eq!(chip.data_rate()?, DataRate::R2Mbps);
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.reset_packet_loss()?;
+    /// assert_eq!(chip.observe_tx()?.lost_packets(), 0);
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn data_rate(&mut self) -> Result<DataRate, TransferError<SPIErr, PinErr>> {
-        self.read_register(Register::RF_SETUP).map(DataRate::from)
+    pub fn reset_packet_loss(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let channel = self.channel()?;
+        self.write_register(Register::RF_CH, channel)
     }
 
-    /// Returns the current power amplifier level as a [`PALevel`] enum.
+    /// Returns the retry statistics accumulated over all transmissions as
+    /// `(packets, retransmissions)`.
+    ///
+    /// Dividing the retransmissions by the packets gives the average number of retries per
+    /// packet, which is a useful metric for tuning a deployment.
+    ///
+    /// # Notes
+    /// [`write()`](#method.write) returns before the packet is transmitted, so its
+    /// retransmissions are only accounted for when the next packet is written.
     ///
     /// # Examples
-    /// ```rust
-    /// // Initialize the chip
-    /// let mut chip = Nrf24l01::new(spi_struct, ce_pin, ncs_pin, delay, NrfConfig::default())?;
-    /// // Default is Min PALevel
-    /// assert_eq!(chip.power_amp_level()?, PALevel::Min);
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// for _ in 0..100 {
+    ///     chip.write(&mut delay, b"Hello")?;
+    /// }
+    /// let (packets, retransmits) = chip.retry_stats();
+    /// let average = retransmits as f32 / packets as f32;
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn power_amp_level(&mut self) -> Result<PALevel, TransferError<SPIErr, PinErr>> {
-        self.read_register(Register::RF_SETUP).map(PALevel::from)
+    pub fn retry_stats(&self) -> (u32, u32) {
+        (self.tx_packets, self.tx_retransmits)
     }
 
-    /// Flush transmission FIFO, used in TX mode.
+    /// Resets the retry statistics, see [`retry_stats()`](#method.retry_stats).
     ///
     /// # Examples
-    /// ```rust
-    /// chip.flush_tx()?;
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.reset_retry_stats();
+    /// assert_eq!(chip.retry_stats(), (0, 0));
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn flush_tx(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
-        self.send_command(Instruction::FTX).map(|_| ())
+    pub fn reset_retry_stats(&mut self) {
+        self.tx_packets = 0;
+        self.tx_retransmits = 0;
+        self.tx_pending = false;
     }
 
-    /// Flush reciever FIFO, used in RX mode.
+    /// Returns `true` if a signal stronger than -64 dBm was detected on the current channel.
+    ///
+    /// Reads the received power detector (`RPD`), called carrier detect (`CD`) on the nRF24L01.
+    /// The chip must be listening for at least 170µs for the value to be valid.
+    /// The bit latches when a packet is received, and can still be read after leaving receive mode.
     ///
     /// # Examples
-    /// ```rust
-    /// nrf24l01.flush_rx()?;
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Find a quiet channel
+    /// for channel in 0..=125 {
+    ///     chip.set_channel(channel)?;
+    ///     chip.start_listening()?;
+    ///     delay.delay_us(200u8);
+    ///     chip.stop_listening()?;
+    ///     if !chip.received_power_detector()? {
+    ///         break;
+    ///     }
+    /// }
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn flush_rx(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
-        self.send_command(Instruction::FRX).map(|_| ())
+    pub fn received_power_detector(&mut self) -> Result<bool, TransferError<SPIErr, PinErr>> {
+        Ok(self.read_register(Register::CD)? & 1 != 0)
     }
 
-    /// Enable CRC encoding scheme.
+    /// Sweeps all channels and counts how often a signal was detected on each channel.
     ///
-    /// **Note** that this configures the nrf24l01 in transmit mode.
+    /// Every channel is sampled `samples_per_channel` times with the received power detector,
+    /// see [`received_power_detector()`](#method.received_power_detector). Each sample puts
+    /// the chip in receive mode for 170µs, the time the detector needs to settle, so a sweep
+    /// takes at least `126 * samples_per_channel * 170µs`.
+    ///
+    /// Afterwards the original channel is restored and the chip is left in transmission mode
+    /// with CE low and the interrupt flags cleared.
     ///
     /// # Examples
-    /// ```rust
-    /// chip.enable_crc(EncodingScheme::R2Bytes)?;
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// let hits = chip.scan_channels(&mut delay, 10)?;
+    /// // Pick the quietest channel
+    /// let (channel, _) = hits.iter().enumerate().min_by_key(|(_, hits)| **hits).unwrap();
+    /// chip.set_channel(channel as u8)?;
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn enable_crc(
-        &mut self,
-        scheme: EncodingScheme,
-    ) -> Result<(), TransferError<SPIErr, PinErr>> {
-        self.write_register(Register::CONFIG, (1 << 3) | (scheme.scheme() << 2))
-    }
-
-    pub fn crc_encoding_scheme(
+    pub fn scan_channels<D: DelayUs<u8>>(
         &mut self,
-    ) -> Result<Option<EncodingScheme>, TransferError<SPIErr, PinErr>> {
-        let config_reg = self.read_register(Register::CONFIG)?;
-        if config_reg & (1 << 3) == 1 << 3 {
-            return Ok(Some(EncodingScheme::from(config_reg)));
+        delay: &mut D,
+        samples_per_channel: u8,
+    ) -> Result<[u8; MAX_CHANNEL as usize + 1], TransferError<SPIErr, PinErr>> {
+        let original_channel = self.channel()?;
+        let mut hits = [0; MAX_CHANNEL as usize + 1];
+        for (channel, hits) in hits.iter_mut().enumerate() {
+            self.set_channel(channel as u8)?;
+            for _ in 0..samples_per_channel {
+                self.start_listening()?;
+                delay.delay_us(Self::RPD_SETTLE_US);
+                self.stop_listening()?;
+                if self.received_power_detector()? {
+                    *hits += 1;
+                }
+            }
         }
-        Ok(None)
+        self.set_channel(original_channel)?;
+        self.reset_status()?;
+        Ok(hits)
     }
 
-    /// Sets the payload size in bytes.
-    /// This can either be static with a set size, or dynamic.
+    /// Set the frequency channel nRF24L01 operates on.
     ///
-    /// `payload_size` can either be an instance of the [`PayloadSize`] enum, or an integer.
+    /// # Arguments
     ///
-    /// # Notes
-    /// * A value of 0 means the dynamic payloads will be enabled.
-    /// * Values bigger than [`MAX_PAYLOAD_SIZE`](constant.MAX_PAYLOAD_SIZE.html) will be set to the maximum.
+    /// * `channel` number between 0 and 125, values outside will be clipped.
     ///
     /// # Examples
-    /// ```rust
-    /// // Two equal methods to set the chip to dynamic payload mode.
-    /// chip.set_payload_size(PayloadSize::Dynamic)?;
-    /// chip.set_payload_size(0)?;
-    /// // Following methods set a static payload size.
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.set_channel(74)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_channel(&mut self, channel: u8) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.write_register(Register::RF_CH, core::cmp::min(channel, MAX_CHANNEL))
+    }
+
+    /// Return the frequency channel nRF24L01 operates on.
+    /// Note that the actual frequency will we the channel +2400 MHz.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// // Initialize the chip
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, NrfConfig::default())?;
+    /// // Default is channel 76
+    /// assert_eq!(chip.channel()?, 76);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn channel(&mut self) -> Result<u8, TransferError<SPIErr, PinErr>> {
+        self.read_register(Register::RF_CH)
+    }
+
+    /// Sets the frequency in MHz the nRF24L01 operates on.
+    ///
+    /// Returns [`TransferError::Config`] if the frequency lies outside of the 2400..=2525 MHz
+    /// range, see [`channel_for_frequency()`](config/fn.channel_for_frequency.html).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.set_frequency_mhz(2476)?;
+    /// assert_eq!(chip.channel()?, 76);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_frequency_mhz(&mut self, mhz: u16) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let channel = channel_for_frequency(mhz).map_err(TransferError::Config)?;
+        self.set_channel(channel)
+    }
+
+    /// Returns the frequency in MHz the nRF24L01 operates on.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Default is channel 76
+    /// assert_eq!(chip.frequency_mhz()?, 2476);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn frequency_mhz(&mut self) -> Result<u16, TransferError<SPIErr, PinErr>> {
+        Ok(BASE_FREQUENCY + self.channel()? as u16)
+    }
+
+    /// Fills `out` with channels that don't overlap at the current data rate, and returns how
+    /// many channels were written.
+    ///
+    /// At 2 Mbps a channel occupies 2 MHz, so the channels are spaced 2 apart. At lower data
+    /// rates every channel is used. Stops when `out` is full.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Build a frequency hopping set
+    /// let mut channels = [0u8; 126];
+    /// let count = chip.recommended_channels(&mut channels)?;
+    /// for channel in channels[..count].iter() {
+    ///     // ...
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn recommended_channels(
+        &mut self,
+        out: &mut [u8],
+    ) -> Result<usize, TransferError<SPIErr, PinErr>> {
+        let spacing = match self.data_rate()? {
+            DataRate::R2Mbps => 2,
+            _ => 1,
+        };
+        let mut count = 0;
+        for (slot, channel) in out.iter_mut().zip((0..=MAX_CHANNEL).step_by(spacing)) {
+            *slot = channel;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Returns the frequency channel and the data rate the nRF24L01 is configured with.
+    ///
+    /// A convenience wrapper that reads the RF_CH and RF_SETUP registers, one SPI transaction
+    /// each, like [`channel()`](#method.channel) and [`data_rate()`](#method.data_rate).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// // Initialize the chip
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, NrfConfig::default())?;
+    /// let (channel, data_rate) = chip.rf_channel_and_rate()?;
+    /// assert_eq!(channel, 76);
+    /// assert_eq!(data_rate, DataRate::R1Mbps);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn rf_channel_and_rate(&mut self) -> Result<(u8, DataRate), TransferError<SPIErr, PinErr>> {
+        let channel = self.read_register(Register::RF_CH)?;
+        let data_rate = DataRate::from(self.read_rf_setup()?);
+        Ok((channel, data_rate))
+    }
+
+    /// Set the address width, saturating values above or below allowed range.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` number between 3 and 5.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.set_address_width(5)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_address_width<T>(&mut self, width: T) -> Result<(), TransferError<SPIErr, PinErr>>
+    where
+        T: Into<AddressWidth>,
+    {
+        let width = width.into();
+        self.write_register(Register::SETUP_AW, width.value())?;
+        self.addr_width = width;
+        Ok(())
+    }
+
+    /// Returns the address width the chip is configured with.
+    ///
+    /// Returns [`TransferError::InvalidRegisterValue`] if the SETUP_AW register holds the illegal
+    /// value 0b00 or has reserved bits set.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.set_address_width(4)?;
+    /// assert_eq!(chip.address_width()?, AddressWidth::R4Bytes);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn address_width(&mut self) -> Result<AddressWidth, TransferError<SPIErr, PinErr>> {
+        let setup_aw = self.read_register(Register::SETUP_AW)?;
+        AddressWidth::from_register(setup_aw).ok_or(TransferError::InvalidRegisterValue(setup_aw))
+    }
+
+    /// Returns the current data rate as a [`DataRate`] enum.
+    ///
+    /// Returns [`TransferError::InvalidRegisterValue`] if the reserved bit of the RF_SETUP register
+    /// is set, or if both data rate bits are set.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// // Initialize the chip
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, NrfConfig::default())?;
+    /// // Default is 1 Mb/s
+    /// assert_eq!(chip.data_rate()?, DataRate::R1Mbps);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn data_rate(&mut self) -> Result<DataRate, TransferError<SPIErr, PinErr>> {
+        self.read_rf_setup().map(DataRate::from)
+    }
+
+    /// Sets the data rate, keeping the power amplifier level and the other RF settings.
+    ///
+    /// Both ends of the link need to use the same data rate.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Fall back to a more robust data rate when the link gets worse
+    /// if chip.link_quality()?.retransmits() > 10 {
+    ///     chip.set_data_rate(DataRate::R1Mbps)?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_data_rate(&mut self, rate: DataRate) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let rf_setup = self.read_register(Register::RF_SETUP)?;
+        self.write_register(
+            Register::RF_SETUP,
+            (rf_setup & !DataRate::bitmask()) | rate.rate(),
+        )?;
+        self.data_rate = rate;
+        Ok(())
+    }
+
+    /// Returns the time in µs a packet with `payload_len` bytes of data occupies the air.
+    ///
+    /// Computed from the data rate, address width, CRC length and payload size kept by the
+    /// driver, so no SPI transaction is made. A packet consists of a 1 byte preamble, the
+    /// address, a 9 bit packet control field, the payload and the CRC. With static payloads,
+    /// the payload size is used instead of `payload_len`, as shorter payloads are padded. The
+    /// software CRC is included, see [`set_software_crc()`](#method.set_software_crc).
+    ///
+    /// Useful to stay within a duty cycle limit.
+    ///
+    /// # Notes
+    /// Only covers a single transmission. The chip needs about 130µs to settle before every
+    /// transmission, and every retransmission and acknowledgement takes up air time as well.
+    /// Auto acknowledgement forces the CRC on, even when it is disabled in the driver.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // 5 byte address, 2 byte CRC, 32 byte payload at 1 Mbps
+    /// assert_eq!(chip.air_time_us(32), 329);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn air_time_us(&self, payload_len: usize) -> u32 {
+        let payload_len = match self.payload_size {
+            PayloadSize::Static(n) => n as u32,
+            PayloadSize::Dynamic if self.software_crc_active() => (payload_len + CRC_LEN) as u32,
+            PayloadSize::Dynamic => payload_len as u32,
+        };
+        let crc_len = match self.config_reg & 0b1100 {
+            0b1000 => 1,
+            0b1100 => 2,
+            _ => 0,
+        };
+        let bits = 8 * (1 + self.addr_width.bytes() as u32 + payload_len + crc_len) + 9;
+        let ns_per_bit = match self.data_rate {
+            DataRate::R2Mbps => 500,
+            DataRate::R1Mbps => 1000,
+            DataRate::R250kbps => 4000,
+        };
+        // Round up to whole microseconds
+        (bits * ns_per_bit + 999) / 1000
+    }
+
+    /// Detects whether the chip is an nRF24L01+ or the original nRF24L01.
+    ///
+    /// Only the nRF24L01+ can set the `RF_DR_LOW` bit in the RF_SETUP register, so the bit is
+    /// set and read back. The original RF setup is restored afterwards.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// if chip.chip_variant()? == ChipVariant::Legacy {
+    ///     // Don't use the 250 kbps data rate
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn chip_variant(&mut self) -> Result<ChipVariant, TransferError<SPIErr, PinErr>> {
+        let rf_setup = self.read_register(Register::RF_SETUP)?;
+        // RF_DR_LOW is the bit that selects 250 kbps
+        let rf_dr_low = DataRate::R250kbps.rate();
+        self.write_register(Register::RF_SETUP, rf_setup | rf_dr_low)?;
+        let readback = self.read_register(Register::RF_SETUP)?;
+        // Restore the original setup
+        self.write_register(Register::RF_SETUP, rf_setup)?;
+        if readback & rf_dr_low != 0 {
+            Ok(ChipVariant::Plus)
+        } else {
+            Ok(ChipVariant::Legacy)
+        }
+    }
+
+    /// Sets the gain of the low noise amplifier.
+    ///
+    /// A high gain improves the receiver sensitivity by about 0.8 dB, at the cost of a higher
+    /// current consumption in receive mode. The data rate and power amplifier level are kept.
+    ///
+    /// # Notes
+    /// Only the original nRF24L01 has this setting, the nRF24L01+ ignores the bit. See
+    /// [`chip_variant()`](#method.chip_variant).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// if chip.chip_variant()? == ChipVariant::Legacy {
+    ///     chip.set_lna_gain(true)?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_lna_gain(&mut self, high: bool) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let rf_setup = self.read_register(Register::RF_SETUP)?;
+        let rf_setup = if high {
+            rf_setup | Self::LNA_HCURR
+        } else {
+            rf_setup & !Self::LNA_HCURR
+        };
+        self.write_register(Register::RF_SETUP, rf_setup)
+    }
+
+    /// Starts transmitting a constant carrier wave at the given power level.
+    ///
+    /// Used for certification testing and antenna tuning, e.g. to measure the output power on a
+    /// spectrum analyzer. No packets are sent in this mode, the output frequency is determined
+    /// by the current channel.
+    /// Puts the chip in transmission mode and holds CE high until
+    /// [`disable_constant_carrier()`](#method.disable_constant_carrier) is called.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.set_channel(40)?;
+    /// chip.enable_constant_carrier(PALevel::Max)?;
+    /// // Measure the output at 2440 MHz
+    /// chip.disable_constant_carrier()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn enable_constant_carrier(
+        &mut self,
+        level: PALevel,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.stop_listening()?;
+        let rf_setup = self.read_register(Register::RF_SETUP)? & !PALevel::bitmask();
+        self.write_register(
+            Register::RF_SETUP,
+            rf_setup | Self::CONT_WAVE | Self::PLL_LOCK | level.level(),
+        )?;
+        self.set_ce_high()
+    }
+
+    /// Stops transmitting the constant carrier wave started with
+    /// [`enable_constant_carrier()`](#method.enable_constant_carrier).
+    ///
+    /// The power level set for the carrier is kept.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.disable_constant_carrier()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn disable_constant_carrier(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.set_ce_low()?;
+        let rf_setup = self.read_register(Register::RF_SETUP)?;
+        self.write_register(
+            Register::RF_SETUP,
+            rf_setup & !(Self::CONT_WAVE | Self::PLL_LOCK),
+        )
+    }
+
+    /// Sets the power amplifier level, keeping the data rate and the other RF settings.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Save power when the receiver is close by
+    /// chip.set_pa_level(PALevel::Low)?;
+    /// assert_eq!(chip.power_amp_level()?, PALevel::Low);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_pa_level(&mut self, level: PALevel) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let rf_setup = self.read_register(Register::RF_SETUP)?;
+        self.write_register(
+            Register::RF_SETUP,
+            (rf_setup & !PALevel::bitmask()) | level.level(),
+        )
+    }
+
+    /// Returns the current power amplifier level as a [`PALevel`] enum.
+    ///
+    /// Returns [`TransferError::InvalidRegisterValue`] if the reserved bit of the RF_SETUP register
+    /// is set, or if both data rate bits are set.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// // Initialize the chip
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, NrfConfig::default())?;
+    /// // Default is Min PALevel
+    /// assert_eq!(chip.power_amp_level()?, PALevel::Min);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn power_amp_level(&mut self) -> Result<PALevel, TransferError<SPIErr, PinErr>> {
+        self.read_rf_setup().map(PALevel::from)
+    }
+
+    /// Flush transmission FIFO, used in TX mode.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.flush_tx()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn flush_tx(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.send_command(Instruction::FTX).map(|_| ())
+    }
+
+    /// Reuses the last transmitted payload, used in TX mode.
+    ///
+    /// The payload stays in the TX FIFO and is resent on every CE pulse, or continuously while
+    /// CE is held high, without loading it over SPI again. This continues until the TX FIFO is
+    /// flushed with [`flush_tx()`](#method.flush_tx) or a new payload is written.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Send a beacon that never changes, CE is held high by write_fast
+    /// chip.write_fast(b"alive")?;
+    /// chip.reuse_tx_payload()?;
+    /// delay.delay_ms(1000u16);
+    /// // Stop sending
+    /// chip.stop_listening()?;
+    /// chip.flush_tx()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn reuse_tx_payload(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.send_command(Instruction::REUSETX).map(|_| ())
+    }
+
+    /// Flush reciever FIFO, used in RX mode.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.flush_rx()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn flush_rx(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.peeked = None;
+        self.send_command(Instruction::FRX).map(|_| ())
+    }
+
+    /// Enable CRC encoding scheme.
+    ///
+    /// Sets both the `EN_CRC` bit and the encoding scheme in the CONFIG register, the other
+    /// bits are left untouched.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.enable_crc(EncodingScheme::R2Bytes)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn enable_crc(
+        &mut self,
+        scheme: EncodingScheme,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.set_crc(Some(scheme))
+    }
+
+    /// Disables the CRC.
+    ///
+    /// Clears both the `EN_CRC` bit and the encoding scheme in the CONFIG register, the other
+    /// bits are left untouched.
+    ///
+    /// # Notes
+    /// Auto acknowledgement forces the CRC on, regardless of the `EN_CRC` bit. Disable auto
+    /// acknowledgement on all pipes to really disable the CRC, see
+    /// [`raw_mode()`](#method.raw_mode).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.disable_crc()?;
+    /// assert_eq!(chip.crc_encoding_scheme()?, None);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn disable_crc(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.set_crc(None)
+    }
+
+    /// Enables or disables the software CRC, used while the hardware CRC is disabled.
+    ///
+    /// When enabled and the hardware CRC is disabled, the driver appends a 2 byte CRC to every
+    /// payload it sends and checks it on every payload it reads, see [`crc`](crate::crc). The
+    /// CRC bytes are part of the payload as the chip sees it, so the maximum length of the
+    /// data is 2 bytes shorter. With static payloads, the data is padded up to 2 bytes less
+    /// than the payload size, and the CRC takes the last 2 bytes.
+    ///
+    /// Reading a payload with a CRC that doesn't match returns [`TransferError::CrcMismatch`].
+    ///
+    /// # Notes
+    /// Has no effect while the hardware CRC is enabled, see
+    /// [`disable_crc()`](#method.disable_crc).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.disable_crc()?;
+    /// chip.set_software_crc(true);
+    /// // Sends 7 bytes, 5 bytes of data followed by the CRC
+    /// chip.write(&mut delay, b"Hello")?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_software_crc(&mut self, enabled: bool) {
+        self.software_crc = enabled;
+    }
+
+    /// Returns the CRC encoding scheme, or `None` if the CRC is disabled.
+    ///
+    /// Returns [`TransferError::InvalidRegisterValue`] if the reserved bit of the CONFIG register
+    /// is set.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// // Initialize the chip
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, NrfConfig::default())?;
+    /// // Default is 2 bytes
+    /// assert_eq!(chip.crc_encoding_scheme()?, Some(EncodingScheme::R2Bytes));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn crc_encoding_scheme(
+        &mut self,
+    ) -> Result<Option<EncodingScheme>, TransferError<SPIErr, PinErr>> {
+        let config_reg = self.read_register(Register::CONFIG)?;
+        if config_reg & Self::CONFIG_RESERVED != 0 {
+            return Err(TransferError::InvalidRegisterValue(config_reg));
+        }
+        if config_reg & (1 << 3) == 1 << 3 {
+            return Ok(Some(EncodingScheme::from(config_reg)));
+        }
+        Ok(None)
+    }
+
+    /// Configures the chip for raw packet experimentation.
+    ///
+    /// Disables auto acknowledgement on all pipes, disables the CRC and enables dynamic payloads.
+    /// This is the most permissive configuration: every payload that matches an address is
+    /// accepted as-is.
+    ///
+    /// # Notes
+    /// Without acknowledgements, payloads are never retransmitted, and without CRC corrupted
+    /// payloads are not discarded. Any reliability has to be provided by the application.
+    ///
+    /// Auto acknowledgement forces the CRC on, which is why it is disabled first.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.raw_mode()?;
+    /// assert_eq!(chip.crc_encoding_scheme()?, None);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn raw_mode(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.write_register(Register::EN_AA, 0)?;
+        self.disable_crc()?;
+        self.set_payload_size(PayloadSize::Dynamic)
+    }
+
+    /// Sets the payload size in bytes.
+    /// This can either be static with a set size, or dynamic.
+    ///
+    /// `payload_size` can either be an instance of the [`PayloadSize`] enum, or an integer.
+    ///
+    /// # Notes
+    /// * A value of 0 means the dynamic payloads will be enabled.
+    /// * Values bigger than [`MAX_PAYLOAD_SIZE`](constant.MAX_PAYLOAD_SIZE.html) will be set to the maximum.
+    /// * A static width is only written to the data pipes that are enabled, to save SPI
+    ///   transactions. The other pipes get it when they are opened, see
+    ///   [`open_reading_pipe()`](#method.open_reading_pipe).
+    /// * Switching from dynamic to static payloads clears the dynamic payload feature and writes
+    ///   0 to the DYNPD register, which disables dynamic payloads on every data pipe, including
+    ///   the pipes set with [`set_dynamic_payload_pipe()`](#method.set_dynamic_payload_pipe).
+    ///   Acknowledgement payloads require dynamic payloads, so these are disabled as well, see
+    ///   [`enable_ack_payloads()`](#method.enable_ack_payloads).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Two equal methods to set the chip to dynamic payload mode.
+    /// chip.set_payload_size(PayloadSize::Dynamic)?;
+    /// chip.set_payload_size(0)?;
+    /// // Following methods set a static payload size.
     /// chip.set_payload_size(12)?; // Messages will be 12 bytes
     /// chip.set_payload_size(PayloadSize::Static(12))?; // Same as previous
     /// chip.set_payload_size(49)?; // Messages will be `MAX_PAYLOAD_SIZE`
+    /// # Ok::<(), Error>(())
     /// ```
     pub fn set_payload_size<T: Into<PayloadSize>>(
         &mut self,
@@ -647,123 +2472,843 @@ where
             PayloadSize::Static(payload_size) => {
                 if self.payload_size == PayloadSize::Dynamic {
                     // currently dynamic payload enabled
-                    // Disable dynamic payloads
-                    let feature = self.read_register(Register::FEATURE)?;
-                    self.write_register(Register::CONFIG, feature & !(1 << 2))?;
+                    // Disable dynamic payloads, and ack payloads which require them
+                    let features = self.features()?;
+                    self.set_features(features.dynamic_payloads(false).ack_payloads(false))?;
+                    self.write_register(Register::DYNPD, 0)?;
                 }
 
-                self.write_register(Register::RX_PW_P0, payload_size)?;
-                self.write_register(Register::RX_PW_P1, payload_size)?;
-                self.write_register(Register::RX_PW_P2, payload_size)?;
-                self.write_register(Register::RX_PW_P3, payload_size)?;
-                self.write_register(Register::RX_PW_P4, payload_size)?;
-                self.write_register(Register::RX_PW_P5, payload_size)?;
+                // The RX_PW registers can't be written in a single burst
+                let open_pipes = self.read_register(Register::EN_RXADDR)?;
+                for pipe in 0..6 {
+                    if open_pipes & (1 << pipe) != 0 {
+                        let register = DataPipe::from(pipe).payload_width_register();
+                        self.write_register(register, payload_size)?;
+                    }
+                }
+                self.rx_payload_widths = [payload_size; 6];
+                self.per_pipe_widths = false;
             }
             PayloadSize::Dynamic => {
-                let feature = self.read_register(Register::FEATURE)?;
-                self.write_register(Register::CONFIG, feature | (1 << 2))?;
-                self.write_register(Register::DYNPD, 0b0001_1111)?; // enable on all pipes
+                let features = self.features()?;
+                self.set_features(features.dynamic_payloads(true))?;
+                self.write_register(Register::DYNPD, 0b0011_1111)?; // enable on all pipes
             }
         }
         self.payload_size = payload_size;
         Ok(())
     }
 
-    /// Returns the payload size as a [`PayloadSize`] enum.
+    /// Sets the static payload size of a single data pipe, in bytes.
+    ///
+    /// Useful to receive different kinds of messages on different pipes, e.g. short commands on
+    /// one pipe and bulk data on another. Reads look up the data pipe of the next payload first
+    /// and read the width of that pipe, which costs an extra SPI transaction, see
+    /// [`read_with_pipe()`](#method.read_with_pipe). Transmissions keep using the size set with
+    /// [`set_payload_size()`](#method.set_payload_size), which also resets all pipes to a
+    /// single size.
+    ///
+    /// # Notes
+    /// * Values bigger than [`MAX_PAYLOAD_SIZE`](constant.MAX_PAYLOAD_SIZE.html) will be set to
+    ///   the maximum.
+    /// * Only has an effect on reads while the driver uses static payloads.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.set_payload_size(PayloadSize::Static(4))?;
+    /// # fn handle_bulk(_: &[u8]) {}
+    /// # fn handle_command(_: DataPipe, _: &[u8]) {}
+    /// chip.set_payload_size_pipe(DataPipe::DP2, 32)?;
+    /// let mut buffer = [0; 32];
+    /// match chip.read_with_pipe(&mut buffer)? {
+    ///     Some((DataPipe::DP2, 32)) => handle_bulk(&buffer),
+    ///     Some((pipe, 4)) => handle_command(pipe, &buffer[..4]),
+    ///     _ => {}
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_payload_size_pipe(
+        &mut self,
+        pipe: DataPipe,
+        size: u8,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let size = core::cmp::min(size, MAX_PAYLOAD_SIZE);
+        self.write_register(pipe.payload_width_register(), size)?;
+        self.rx_payload_widths[pipe.pipe() as usize] = size;
+        self.per_pipe_widths = true;
+        Ok(())
+    }
+
+    /// Enables or disables dynamic payloads on a single data pipe.
+    ///
+    /// Pipes without dynamic payloads use the static width set with
+    /// [`set_payload_size()`](#method.set_payload_size). Dynamic payloads are enabled in the
+    /// FEATURE register as long as any pipe uses them. Acknowledgement payloads require dynamic
+    /// payloads on data pipe 0, so disabling them on that pipe disables acknowledgement payloads
+    /// as well, see [`enable_ack_payloads()`](#method.enable_ack_payloads).
+    ///
+    /// # Notes
+    /// Reads use the payload size of the driver, see [`payload_size()`](#method.payload_size).
+    /// To mix static and dynamic pipes, set the static width first, then switch the driver to
+    /// dynamic payloads and disable them on the static pipes.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Fixed 8 byte commands on pipe 0, variable telemetry on the other pipes
+    /// chip.set_payload_size(8)?;
+    /// chip.set_payload_size(PayloadSize::Dynamic)?;
+    /// chip.set_dynamic_payload_pipe(DataPipe::DP0, false)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_dynamic_payload_pipe(
+        &mut self,
+        pipe: DataPipe,
+        enabled: bool,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let dynpd = self.read_register(Register::DYNPD)?;
+        let dynpd = if enabled {
+            dynpd | (1 << pipe.pipe())
+        } else {
+            dynpd & !(1 << pipe.pipe())
+        };
+        let features = self.features()?;
+        // Ack payloads need dynamic payloads on data pipe 0, see `verify_config`
+        let ack_payloads = features.ack_payloads_enabled() && dynpd & 1 != 0;
+        self.set_features(
+            features
+                .dynamic_payloads(dynpd != 0)
+                .ack_payloads(ack_payloads),
+        )?;
+        self.write_register(Register::DYNPD, dynpd)
+    }
+
+    /// Returns the payload size as a [`PayloadSize`] enum.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// // Initialize chip
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, NrfConfig::default())?;
+    /// // Default payload size is MAX_PAYLOAD_SIZE
+    /// assert_eq!(chip.payload_size(), PayloadSize::Static(MAX_PAYLOAD_SIZE));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn payload_size(&self) -> PayloadSize {
+        self.payload_size
+    }
+
+    /// Returns `true` if a value of type `T` fits in a single payload.
+    ///
+    /// For static payloads this checks against the configured payload size, for dynamic
+    /// payloads against [`MAX_PAYLOAD_SIZE`]. Larger values would be truncated over the air.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// let config = NrfConfig::default().payload_size(PayloadSize::Static(4));
+    /// let chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config)?;
+    /// assert!(chip.payload_fits::<f32>());
+    /// assert!(!chip.payload_fits::<f64>());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn payload_fits<T>(&self) -> bool {
+        core::mem::size_of::<T>() <= self.max_payload_len()
+    }
+
+    /// Enables the dynamic acknowledgement feature (`EN_DYN_ACK`).
+    ///
+    /// This allows individual payloads to be sent without requesting an acknowledgement.
+    /// Without this bit set, the chip silently ignores the no-ack transmit command.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.enable_dynamic_ack()?;
+    /// assert!(chip.dynamic_ack_enabled()?);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn enable_dynamic_ack(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let features = self.features()?;
+        self.set_features(features.dynamic_ack(true))
+    }
+
+    /// Returns `true` if the dynamic acknowledgement feature (`EN_DYN_ACK`) is enabled.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// if !chip.dynamic_ack_enabled()? {
+    ///     chip.enable_dynamic_ack()?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn dynamic_ack_enabled(&mut self) -> Result<bool, TransferError<SPIErr, PinErr>> {
+        Ok(self.features()?.dynamic_ack_enabled())
+    }
+
+    /// Enables acknowledgement payloads (`EN_ACK_PAY`).
+    ///
+    /// Acknowledgement payloads require dynamic payloads, so these are enabled on all pipes as
+    /// well, see [`set_payload_size()`](#method.set_payload_size).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.enable_ack_payloads()?;
+    /// assert_eq!(chip.payload_size(), PayloadSize::Dynamic);
+    /// chip.write_ack_payload(DataPipe::DP1, b"ack")?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn enable_ack_payloads(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        if self.payload_size != PayloadSize::Dynamic {
+            self.set_payload_size(PayloadSize::Dynamic)?;
+        }
+        let features = self.features()?;
+        self.set_features(features.ack_payloads(true))
+    }
+
+    /// Returns `true` if acknowledgement payloads (`EN_ACK_PAY`) are enabled.
+    ///
+    /// A chip reset clears this bit, which is a common reason for acknowledgement payloads to
+    /// go missing.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// if !chip.ack_payload_enabled()? {
+    ///     // Acknowledgement payloads will not be sent
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn ack_payload_enabled(&mut self) -> Result<bool, TransferError<SPIErr, PinErr>> {
+        Ok(self.features()?.ack_payloads_enabled())
+    }
+
+    /// Sets the optional features of the chip in the FEATURE register. See [`Features`].
+    ///
+    /// All three features are written at once. To change a single feature, modify the result
+    /// of [`features()`](#method.features).
+    ///
+    /// # Notes
+    /// Dynamic payloads are also managed by [`set_payload_size()`](#method.set_payload_size),
+    /// which the driver relies on to read payloads. Prefer that method for dynamic payloads.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// let features = chip.features()?.dynamic_ack(true);
+    /// chip.set_features(features)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_features(
+        &mut self,
+        features: Features,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.write_register(Register::FEATURE, features.value())
+    }
+
+    /// Returns the optional features of the chip, read from the FEATURE register.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// let features = chip.features()?;
+    /// if features.ack_payloads_enabled() && !features.dynamic_payloads_enabled() {
+    ///     // Acknowledgement payloads won't work
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn features(&mut self) -> Result<Features, TransferError<SPIErr, PinErr>> {
+        self.read_register(Register::FEATURE).map(Features::from)
+    }
+
+    /// Powers the chip up. Note that a new initialized device will already be in power up mode, so
+    /// calling [`power_up()`](#method.power_up) is not necessary.
+    ///
+    /// Should be called after [`power_down()`](#method.power_down) to put the chip back into power up mode.
+    /// Waits 5ms for the oscillator to start up (`Tpd2stby`) before returning.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Go to sleep
+    /// chip.power_down()?;
+    /// // Zzz
+    /// // ...
+    /// chip.power_up(&mut delay)?; // power back up
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn power_up<D>(&mut self, delay: &mut D) -> Result<(), TransferError<SPIErr, PinErr>>
+    where
+        D: DelayMs<u8>,
+    {
+        // if not powered up, power up and wait for the radio to initialize
+        if !self.is_powered_up() {
+            // update the stored config register
+            self.config_reg |= 1 << 1;
+            self.write_register(Register::CONFIG, self.config_reg)?;
+
+            delay.delay_ms(5);
+        }
+        Ok(())
+    }
+
+    /// Powers the chip down. This is the low power mode.
+    /// The chip will consume approximatly 900nA.
+    ///
+    /// Powering down takes effect immediately, so no delay is needed.
+    /// To power the chip back up, call [`power_up()`](#method.power_up).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Go to sleep
+    /// chip.power_down()?;
+    /// // Zzz
+    /// // ...
+    /// chip.power_up(&mut delay)?; // power back up
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn power_down(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.set_ce_low()?;
+        self.config_reg &= !(1 << 1);
+        self.write_register(Register::CONFIG, self.config_reg)?;
+        Ok(())
+    }
+
+    /// Consumes the driver and returns the SPI instance and the pins, e.g. to reuse the SPI bus
+    /// for another device.
+    ///
+    /// The chip is left in its current state, see [`release()`](#method.release) to power it
+    /// down first.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.power_down()?;
+    /// let (spi, ce, ncs) = chip.into_inner();
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn into_inner(self) -> (SPI, CE, NCS) {
+        (self.spi, self.ce, self.ncs)
+    }
+
+    /// Powers the chip down, then consumes the driver and returns the SPI instance and the pins.
+    ///
+    /// # Notes
+    /// If powering down fails, the driver is dropped together with the peripherals. Call
+    /// [`power_down()`](#method.power_down) and [`into_inner()`](#method.into_inner) instead to
+    /// keep them on failure.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # struct OtherDevice;
+    /// # impl OtherDevice { fn new(_: Spi, _: Pin) -> Self { OtherDevice } }
+    /// # let other_ncs = Pin;
+    /// let (spi, ce, ncs) = chip.release()?;
+    /// let other_device = OtherDevice::new(spi, other_ncs);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn release(mut self) -> Result<(SPI, CE, NCS), TransferError<SPIErr, PinErr>> {
+        self.power_down()?;
+        Ok(self.into_inner())
+    }
+
+    /// Puts the chip to sleep in a clean state.
+    ///
+    /// Flushes both FIFOs, clears all interrupt flags, brings CE low and powers the chip down.
+    /// The role of the chip is kept, so [`wake()`](#method.wake) can restore it.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.start_listening()?;
+    /// // ...
+    /// chip.sleep()?;
+    /// // Deep sleep of the MCU
+    /// chip.wake(&mut delay)?; // Listening again
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn sleep(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.flush_tx()?;
+        self.flush_rx()?;
+        self.reset_status()?;
+        self.power_down()
+    }
+
+    /// Wakes the chip up after [`sleep()`](#method.sleep).
+    ///
+    /// Powers the chip up and waits for the radio to initialize. If the chip was listening,
+    /// CE is brought high again so it resumes listening.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.sleep()?;
+    /// // ...
+    /// chip.wake(&mut delay)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn wake<D>(&mut self, delay: &mut D) -> Result<(), TransferError<SPIErr, PinErr>>
+    where
+        D: DelayMs<u8>,
+    {
+        self.power_up(delay)?;
+        if self.config_reg & 1 != 0 {
+            self.set_ce_high()?;
+        }
+        Ok(())
+    }
+
+    /// Reads the status register from device. See [`Status`].
+    ///
+    /// Returns [`TransferError::InvalidStatus`] if the status is not valid, see
+    /// [`Status::is_valid()`].
+    pub fn status(&mut self) -> Result<Status, TransferError<SPIErr, PinErr>> {
+        let status = self.send_command(Instruction::NOP)?;
+        if !status.is_valid() {
+            return Err(TransferError::InvalidStatus);
+        }
+        Ok(status)
+    }
+
+    /// Returns the status received during the most recent SPI transfer, without using the bus.
+    ///
+    /// Every command, register read and payload transfer clocks out the status register, so
+    /// after e.g. [`write()`](#method.write) or [`read()`](#method.read) this is the status the
+    /// chip reported at that time. Register writes don't update it. Before the first transfer,
+    /// the reset value of the status register is returned.
+    ///
+    /// # Notes
+    /// The status may be stale, use [`status()`](#method.status) to read the current value.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.write(&mut delay, b"Hello")?;
+    /// if chip.last_status().reached_max_retries() {
+    ///     // Not acknowledged
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn last_status(&self) -> Status {
+        self.last_status
+    }
+
+    /// Reads the status register and recovers the chip from error conditions.
+    ///
+    /// * If the maximum number of retries was reached, the TX FIFO is flushed.
+    /// * If dynamic payloads are enabled and the payload at the top of the RX FIFO has a
+    ///   corrupt width, the RX FIFO is flushed, see [`rx_width_valid()`](#method.rx_width_valid).
+    ///
+    /// Afterwards all interrupt flags are cleared. Returns the status from before the recovery,
+    /// so the caller can tell what went wrong.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// let status = chip.status_and_recover()?;
+    /// if status.reached_max_retries() {
+    ///     // The pending payloads were dropped
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn status_and_recover(&mut self) -> Result<Status, TransferError<SPIErr, PinErr>> {
+        let status = self.status()?;
+        if status.reached_max_retries() {
+            self.flush_tx()?;
+        }
+        if self.payload_size == PayloadSize::Dynamic && status.data_pipe_available().is_some() {
+            // Flushes the RX FIFO if the width is corrupt
+            self.rx_width_valid()?;
+        }
+        self.reset_status()?;
+        Ok(status)
+    }
+
+    /// Resets the chip to a known state, without changing its configuration.
+    ///
+    /// * Both the TX and RX FIFO are flushed.
+    /// * All interrupt flags are cleared.
+    /// * The lost packets counter is reset, see [`reset_packet_loss()`](#method.reset_packet_loss).
+    /// * The CONFIG register is rewritten from the driver's copy. If the chip is powered up,
+    ///   this waits for the radio to initialize, in case it was reset by a brown-out.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// if let Err(TransferError::MaximumRetries) = chip.write(&mut delay, b"Hello") {
+    ///     chip.reset(&mut delay)?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn reset<D>(&mut self, delay: &mut D) -> Result<(), TransferError<SPIErr, PinErr>>
+    where
+        D: DelayMs<u8>,
+    {
+        self.flush_tx()?;
+        self.flush_rx()?;
+        self.reset_status()?;
+        self.reset_packet_loss()?;
+        self.write_register(Register::CONFIG, self.config_reg)?;
+        if self.is_powered_up() {
+            delay.delay_ms(5);
+        }
+        Ok(())
+    }
+
+    /// Checks the configuration of the chip for combinations of settings that silently break
+    /// communication.
+    ///
+    /// Reads the FEATURE, DYNPD, EN_AA and CONFIG registers, and returns
+    /// [`TransferError::InconsistentConfig`] with the first [`Inconsistency`] that is found.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (spi, ce, ncs, mut delay) = peripherals();
+    /// # let config = NrfConfig::default();
+    /// let mut chip = Nrf24l01::new(spi, ce, ncs, &mut delay, config)?;
+    /// // ... further setup
+    /// chip.verify_config()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn verify_config(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let features = self.features()?;
+        let dynpd = self.read_register(Register::DYNPD)?;
+        let en_aa = self.read_register(Register::EN_AA)?;
+        let config = self.read_register(Register::CONFIG)?;
+
+        let en_dpl = features.dynamic_payloads_enabled();
+        let en_ack_pay = features.ack_payloads_enabled();
+        let inconsistency = if en_ack_pay && (!en_dpl || dynpd & 1 == 0) {
+            Some(Inconsistency::AckPayloadsWithoutDynamicPayloads)
+        } else if en_ack_pay && en_aa & 1 == 0 {
+            Some(Inconsistency::AckPayloadsWithoutAutoAck)
+        } else if dynpd & 0b0011_1111 != 0 && !en_dpl {
+            Some(Inconsistency::DynamicPayloadsWithoutFeature)
+        } else if en_aa & 0b0011_1111 != 0 && config & (1 << 3) == 0 {
+            Some(Inconsistency::CrcForcedByAutoAck)
+        } else {
+            None
+        };
+        match inconsistency {
+            Some(inconsistency) => Err(TransferError::InconsistentConfig(inconsistency)),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the register at address `addr`.
+    ///
+    /// An escape hatch for registers and chip features the driver doesn't model. Only the
+    /// lower 5 bits of `addr` are used.
+    ///
+    /// Only available with the `unstable-raw` feature.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // OBSERVE_TX
+    /// let observe_tx = chip.read_register_raw(0x08)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    #[cfg(any(test, feature = "unstable-raw"))]
+    pub fn read_register_raw(&mut self, addr: u8) -> Result<u8, TransferError<SPIErr, PinErr>> {
+        self.tx_buf[..2].copy_from_slice(&[Instruction::RR.opcode() | (addr & 0x1f), 0]);
+        self.set_ncs_low()?;
+        let reg = self.spi_transfer_tx_buf(1)?[1];
+        self.set_ncs_high()?;
+        Ok(reg)
+    }
+
+    /// Writes `value` to the register at address `addr`.
+    ///
+    /// An escape hatch for registers and chip features the driver doesn't model. Only the
+    /// lower 5 bits of `addr` are used.
+    ///
+    /// # Warnings
+    /// The driver keeps part of the configuration in memory, e.g. the CONFIG register and the
+    /// payload size. Changing those registers directly will bring the driver out of sync.
+    ///
+    /// Only available with the `unstable-raw` feature.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Enable dynamic payloads on data pipe 1 only
+    /// chip.write_register_raw(0x1c, 0b10)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    #[cfg(any(test, feature = "unstable-raw"))]
+    pub fn write_register_raw(
+        &mut self,
+        addr: u8,
+        value: u8,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.tx_buf[..2].copy_from_slice(&[Instruction::WR.opcode() | (addr & 0x1f), value]);
+        self.set_ncs_low()?;
+        self.spi_write_tx_buf(1)?;
+        self.set_ncs_high()
+    }
+
+    /// Reads the CONFIG register from device. See [`ConfigRegister`].
     ///
     /// # Examples
-    /// ```rust
-    /// // Initialize chip
-    /// let mut chip = Nrf24l01::new(spi_struct, ce_pin, ncs_pin, delay, NrfConfig::default())?;
-    /// // Default payload size is MAX_PAYLOAD_SIZE
-    /// assert_eq!(chip.payload_size()?, PayloadSize::Static(MAX_PAYLOAD_SIZE));
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// let config = chip.config_register()?;
+    /// if config.is_rx() {
+    ///     // PRIM_RX is still set, the chip can't transmit
+    /// }
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn payload_size(&self) -> PayloadSize {
-        self.payload_size
+    pub fn config_register(&mut self) -> Result<ConfigRegister, TransferError<SPIErr, PinErr>> {
+        self.read_register(Register::CONFIG)
+            .map(ConfigRegister::from)
     }
 
-    /// Powers the chip up. Note that a new initialized device will already be in power up mode, so
-    /// calling [`power_up()`](#method.power_up) is not necessary.
-    ///
-    /// Should be called after [`power_down()`](#method.power_down) to put the chip back into power up mode.
+    /// Reads the FIFO status register from device. See [`FIFOStatus`].
     ///
     /// # Examples
-    /// ```rust
-    /// // Go to sleep
-    /// chip.power_down(&mut delay)?;
-    /// // Zzz
-    /// // ...
-    /// chip.power_up(&mut delay)?; // power back up
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # let mut buffer = [0u8; 32];
+    /// // Drain the receive queue
+    /// while !chip.fifo_status()?.rx_empty() {
+    ///     chip.read(&mut buffer)?;
+    /// }
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn power_up<D>(&mut self, delay: &mut D) -> Result<(), TransferError<SPIErr, PinErr>>
-    where
-        D: DelayMs<u8>,
-    {
-        // if not powered up, power up and wait for the radio to initialize
-        if !self.is_powered_up() {
-            // update the stored config register
-            self.config_reg |= 1 << 1;
-            self.write_register(Register::CONFIG, self.config_reg)?;
-
-            delay.delay_ms(5);
-        }
-        Ok(())
+    pub fn fifo_status(&mut self) -> Result<FIFOStatus, TransferError<SPIErr, PinErr>> {
+        self.read_register(Register::FIFO_STATUS)
+            .map(FIFOStatus::from)
     }
 
-    /// Powers the chip down. This is the low power mode.
-    /// The chip will consume approximatly 900nA.
+    /// Returns `true` if the RX FIFO is full while the data ready flag is still set, meaning
+    /// payloads arrive faster than they are read.
     ///
-    /// To power the chip back up, call [`power_up()`](#method.power_up).
+    /// The RX FIFO holds 3 payloads. Payloads that arrive while it is full are dropped, and the
+    /// chip doesn't count them, so this is the best indication that data is being lost. Drain
+    /// the RX FIFO more often, e.g. with [`read_all()`](#method.read_all), when this returns
+    /// `true`.
+    ///
+    /// Uses a single SPI transaction, as the status is clocked out while FIFO_STATUS is read.
     ///
     /// # Examples
-    /// ```rust
-    /// // Go to sleep
-    /// chip.power_down(&mut delay)?;
-    /// // Zzz
-    /// // ...
-    /// chip.power_up(&mut delay)?; // power back up
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # fn warn_operator() {}
+    /// # fn handle(_: DataPipe, _: &[u8]) {}
+    /// if chip.rx_overflow()? {
+    ///     warn_operator();
+    /// }
+    /// chip.read_all(|pipe, data| handle(pipe, data))?;
+    /// # Ok::<(), Error>(())
     /// ```
-    pub fn power_down(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
-        self.set_ce_low()?;
-        self.config_reg &= !(1 << 1);
-        self.write_register(Register::CONFIG, self.config_reg)?;
-        Ok(())
-    }
-
-    /// Reads the status register from device. See [`Status`].
-    pub fn status(&mut self) -> Result<Status, TransferError<SPIErr, PinErr>> {
-        self.send_command(Instruction::NOP)
+    pub fn rx_overflow(&mut self) -> Result<bool, TransferError<SPIErr, PinErr>> {
+        let rx_full = self.fifo_status()?.rx_full();
+        Ok(rx_full && self.last_status.data_ready())
     }
 
     /// Resets the following flags in the status register:
     /// - data ready RX fifo interrupt
     /// - data sent TX fifo interrupt
     /// - maximum number of number of retries interrupt
+    ///
+    /// Same as `clear_interrupts(Interrupts::all())`. To keep some of the flags set, e.g. to
+    /// handle them later, use [`clear_interrupts()`](#method.clear_interrupts) instead.
     pub fn reset_status(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
-        self.write_register(Register::STATUS, Self::STATUS_RESET)
+        self.clear_interrupts(Interrupts::all())
+    }
+
+    /// Clears only the given interrupt flags in the status register.
+    ///
+    /// The flags are cleared by writing 1 to them, so the other flags are left untouched.
+    /// Useful when different parts of an application handle different interrupts.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// // Acknowledge data ready, the transmission flags are handled elsewhere
+    /// chip.clear_interrupts(Interrupts::new().data_ready())?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn clear_interrupts(
+        &mut self,
+        irq: Interrupts,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.write_register(Register::STATUS, irq.value())
+    }
+
+    /// Returns whether the data sent flag is set, and clears only that flag if it is.
+    ///
+    /// The data ready and maximum retries flags are left untouched.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.write(&mut delay, b"Hello")?;
+    /// while !chip.take_tx_sent()? {
+    ///     // Wait for the packet to be sent
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn take_tx_sent(&mut self) -> Result<bool, TransferError<SPIErr, PinErr>> {
+        let sent = self.status()?.data_sent();
+        if sent {
+            self.clear_interrupts(Interrupts::new().transmission_ok())?;
+        }
+        Ok(sent)
     }
 
     /// Sets the selected interrupt flags.
     ///
     /// Configures which events will trigger the IRQ pin to active low.
+    /// Events that are not selected are masked, see [`mask_interrupts()`](#method.mask_interrupts).
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
     /// let interrupts = Interrupts::new().transmission_fail().transmission_ok();
     /// chip.set_interrupts(interrupts)?;
+    /// # Ok::<(), Error>(())
     /// ```
     /// Enable all interrupts.
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
     /// let interrupts = Interrupts::all();
-    /// chip.set_interrupt(interrupts);
+    /// chip.set_interrupts(interrupts)?;
+    /// # Ok::<(), Error>(())
     /// ```
     pub fn set_interrupts(&mut self, irq: Interrupts) -> Result<(), TransferError<SPIErr, PinErr>> {
-        // Clear interrupt flags
+        // The mask bits are active high, so mask all other interrupts
+        self.mask_interrupts(Interrupts::from(!irq.value()))
+    }
+
+    /// Masks the selected interrupts.
+    ///
+    /// Masked events will not trigger the IRQ pin, all other events will.
+    /// Their flags are still set in the status register.
+    ///
+    /// # Examples
+    /// Disable the IRQ pin entirely.
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.mask_interrupts(Interrupts::all())?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn mask_interrupts(
+        &mut self,
+        irq: Interrupts,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        // Clear interrupt mask bits
         self.config_reg &= !Interrupts::all().value();
-        // Set configured interrupt flags
+        // Set configured interrupt mask bits
         self.config_reg |= irq.value();
-        self.write_register(Register::STATUS, self.config_reg)?;
-        Ok(())
+        self.write_register(Register::CONFIG, self.config_reg)
+    }
+
+    /// Configures the chip for interrupt driven operation and stores the IRQ pin.
+    ///
+    /// Enables exactly the `enabled` interrupts on the IRQ pin and clears any stale interrupt
+    /// flags, so the pin is released until a new event occurs. Returns the driver together with
+    /// `irq` as a [`Nrf24l01WithIrq`], use
+    /// [`poll_irq()`](struct.Nrf24l01WithIrq.html#method.poll_irq) to handle the interrupts.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// # let (irq, mut buffer) = (Pin, [0u8; 32]);
+    /// let mut chip = chip.setup_interrupts(Interrupts::new().data_ready(), irq)?;
+    /// chip.start_listening()?;
+    /// loop {
+    ///     if let Some(interrupts) = chip.poll_irq()? {
+    ///         if interrupts.contains(InterruptKind::DataReady) {
+    ///             chip.read(&mut buffer)?;
+    ///         }
+    ///     }
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    ///
+    /// Only available with the `irq` feature.
+    #[cfg(feature = "irq")]
+    pub fn setup_interrupts<IRQ: InputPin<Error = PinErr>>(
+        mut self,
+        enabled: Interrupts,
+        irq: IRQ,
+    ) -> Result<Nrf24l01WithIrq<SPI, CE, NCS, IRQ>, TransferError<SPIErr, PinErr>> {
+        self.set_interrupts(enabled)?;
+        self.reset_status()?;
+        Ok(Nrf24l01WithIrq::new(self, irq))
+    }
+
+    /// Returns the interrupts that are currently masked in the CONFIG register.
+    ///
+    /// Masked interrupts are **not** reflected on the IRQ pin. Their flags are still set in
+    /// the status register.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// let masked = chip.interrupt_mask()?;
+    /// if masked.contains(InterruptKind::DataReady) {
+    ///     // The IRQ pin will not go low when data arrives
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn interrupt_mask(&mut self) -> Result<Interrupts, TransferError<SPIErr, PinErr>> {
+        self.read_register(Register::CONFIG).map(Interrupts::from)
+    }
+
+    /// Returns which events are signaled on the IRQ pin. See [`IrqPinConfig`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// chip.set_interrupts(Interrupts::new().data_ready())?;
+    /// let irq = chip.irq_pin_config()?;
+    /// assert!(irq.rx_dr_on_pin);
+    /// assert!(!irq.tx_ds_on_pin);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn irq_pin_config(&mut self) -> Result<IrqPinConfig, TransferError<SPIErr, PinErr>> {
+        self.interrupt_mask().map(IrqPinConfig::from)
     }
 
     /// Query which interrupts were triggered.
@@ -772,14 +3317,18 @@ where
     pub fn interrupt_src(&mut self) -> Result<Interrupts, TransferError<SPIErr, PinErr>> {
         let status = self.status()?;
         // Clear flags
-        self.write_register(Register::STATUS, Interrupts::all().value())?;
+        self.reset_status()?;
         Ok(Interrupts::from(status.value()))
     }
 
     /// Returns a debug struct for printing information regarding current setup
     ///
     /// # Example
-    /// ```rust
+    /// ```no_run
+    /// # use nrf24_rs::doctest::*;
+    /// # let (mut chip, mut delay) = setup();
+    /// println!("{:?}", chip.debug_view()?);
+    /// # Ok::<(), Error>(())
     /// ```
     pub fn debug_view(
         &mut self,
@@ -799,10 +3348,10 @@ where
             Mode::ReceiverMode
         };
 
-        let addr_width = AddressWidth::from_register(self.read_register(Register::SETUP_AW)?);
+        let addr_width = self.address_width()?;
 
-        let tx_addr = self.read_tx()?;
-        let rx1_addr = self.read_rx()?;
+        let tx_addr = self.tx_address_array()?;
+        let rx1_addr = self.read_address(Register::RX_ADDR_P1)?;
         let auto_ack = self.read_register(Register::EN_AA)?;
         let open_read_pipes = self.read_register(Register::EN_RXADDR)?;
 
@@ -833,87 +3382,396 @@ where
         self.send_command_bytes(instruction, &[])
     }
 
-    // Sends an instruction with some payload data over the SPI bus
-    //
-    // Returns the status from the device
-    fn send_command_bytes(
+    // Sends an instruction with some payload data over the SPI bus
+    //
+    // Returns the status from the device
+    fn send_command_bytes(
+        &mut self,
+        instruction: Instruction,
+        buf: &[u8],
+    ) -> Result<Status, TransferError<SPIErr, PinErr>> {
+        // Use tx buffer to copy the values into
+        // First byte will be the opcode
+        self.tx_buf[0] = instruction.opcode();
+        self.tx_buf[1..=buf.len()].copy_from_slice(buf);
+        // Write to spi
+        self.set_ncs_low()?;
+        let r = self.spi_transfer_tx_buf(buf.len())?;
+        let status = Status::from(r[0]);
+        self.set_ncs_high()?;
+
+        Ok(status)
+    }
+
+    /// Writes values to a given register.
+    ///
+    /// This can be anything that can be viewed as a buffer of u8's.
+    /// `IntoBuf` is currently implemented for T and for &[T].
+    /// This means that this function can be polymorphically called for single value writes as well
+    /// as for arrays.
+    fn write_register<T: IntoBuf<u8>>(
+        &mut self,
+        register: Register,
+        buf: T,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let buf = buf.into_buf();
+        // Use tx buffer to copy the values into
+        // First byte will be the opcode
+        self.tx_buf[0] = Instruction::WR.opcode() | register.addr();
+        // Copy over the values
+        self.tx_buf[1..=buf.len()].copy_from_slice(buf);
+        // Write to spi
+        self.set_ncs_low()?;
+        self.spi_write_tx_buf(buf.len())?;
+        self.set_ncs_high()?;
+
+        Ok(())
+    }
+
+    fn read_register(&mut self, register: Register) -> Result<u8, TransferError<SPIErr, PinErr>> {
+        let mut buf = [0];
+        self.read_register_bytes(register, &mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Reads `buf.len()` bytes from a register, used for the multi-byte address registers.
+    fn read_register_bytes(
+        &mut self,
+        register: Register,
+        buf: &mut [u8],
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let len = buf.len();
+        self.tx_buf[0] = Instruction::RR.opcode() | register.addr();
+        self.tx_buf[1..=len].fill(0);
+        self.set_ncs_low()?;
+        let r = self.spi_transfer_tx_buf(len)?;
+        // Skip first byte because it contains the status.
+        buf.copy_from_slice(&r[1..=len]);
+        self.set_ncs_high()
+    }
+
+    /// Reads the payload at the top of the RX FIFO into `buf`.
+    ///
+    /// Returns the status from before the FIFO advanced, and the number of bytes read.
+    /// Returns [`TransferError::BufferTooSmall`] without reading the payload if it doesn't fit
+    /// in `buf`.
+    fn read_payload(
+        &mut self,
+        buf: &mut [u8],
+    ) -> Result<(Status, usize), TransferError<SPIErr, PinErr>> {
+        if let Some((status, len)) = self.peeked {
+            let len = len as usize;
+            if len > buf.len() {
+                return Err(TransferError::BufferTooSmall { len: len as u8 });
+            }
+            self.peeked = None;
+            buf[..len].copy_from_slice(&self.peek_buf[..len]);
+            return Ok((status, len));
+        }
+        let software_crc = self.software_crc_active();
+        let len = match self.payload_size {
+            PayloadSize::Static(n) if self.per_pipe_widths => {
+                // The width depends on the pipe the payload was received on
+                match self.status()?.data_pipe_available() {
+                    Some(pipe) => self.rx_payload_widths[pipe.pipe() as usize] as usize,
+                    None => n as usize,
+                }
+            }
+            PayloadSize::Static(n) => n as usize,
+            PayloadSize::Dynamic => {
+                // Returns an error and flushes the RX FIFO if the width is corrupt
+                self.dynamic_payload_length()? as usize
+            }
+        };
+        // The CRC covers the whole payload, but is not copied into `buf`
+        let data_len = if software_crc {
+            len.saturating_sub(CRC_LEN)
+        } else {
+            len
+        };
+        if data_len > buf.len() {
+            return Err(TransferError::BufferTooSmall {
+                len: data_len as u8,
+            });
+        }
+
+        // Use tx buffer to copy the values into
+        // First byte will be the opcode, followed by dummy bytes to clock out the payload
+        self.tx_buf[0] = Instruction::RRX.opcode();
+        self.tx_buf[1..=len].fill(0);
+        // Write to spi
+        self.set_ncs_low()?;
+        let r = self.spi_transfer_tx_buf(len)?;
+        let status = Status::from(r[0]);
+        let crc_ok = !software_crc || crc::verify_crc(&r[1..=len]);
+        // Transfer the data read to buf.
+        // Skip first byte because it contains the command.
+        // Make both slices are the same length, otherwise `copy_from_slice` panics.
+        buf[..data_len].copy_from_slice(&r[1..=data_len]);
+        self.set_ncs_high()?;
+
+        if !crc_ok {
+            return Err(TransferError::CrcMismatch);
+        }
+        Ok((status, data_len))
+    }
+
+    /// Checks that the length of an address matches the configured address width.
+    fn check_address_length(&self, addr: &[u8]) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let width = self.addr_width.bytes();
+        if addr.len() != width as usize {
+            return Err(TransferError::InvalidAddressLength {
+                expected: width,
+                got: addr.len() as u8,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks the length of a receive address. Data pipes 2 to 5 also accept a single byte.
+    fn check_rx_address_length(
+        &self,
+        pipe: DataPipe,
+        addr: &[u8],
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        match pipe {
+            DataPipe::DP0 | DataPipe::DP1 => self.check_address_length(addr),
+            _ if addr.len() == 1 => Ok(()),
+            _ => self.check_address_length(addr),
+        }
+    }
+
+    /// Reads the RF_SETUP register, and checks that the reserved bit is not set and that
+    /// RF_DR_LOW and RF_DR_HIGH are not both set.
+    fn read_rf_setup(&mut self) -> Result<u8, TransferError<SPIErr, PinErr>> {
+        let rf_setup = self.read_register(Register::RF_SETUP)?;
+        if rf_setup & Self::RF_SETUP_RESERVED != 0
+            || rf_setup & DataRate::bitmask() == DataRate::bitmask()
+        {
+            return Err(TransferError::InvalidRegisterValue(rf_setup));
+        }
+        Ok(rf_setup)
+    }
+
+    /// Reads the full 5 byte address from an address register.
+    fn read_address(
+        &mut self,
+        register: Register,
+    ) -> Result<[u8; 5], TransferError<SPIErr, PinErr>> {
+        let mut buf = [0; 5];
+        self.read_register_bytes(register, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Writes a payload into the TX FIFO.
+    ///
+    /// In static payload mode, the payload is padded with zeroes up to the payload size.
+    /// Returns the status received from the device.
+    fn load_payload(
+        &mut self,
+        instruction: Instruction,
+        buf: &[u8],
+    ) -> Result<Status, TransferError<SPIErr, PinErr>> {
+        let max = self.max_payload_len();
+        if buf.len() > max {
+            return Err(TransferError::BufferTooLarge { max: max as u8 });
+        }
+        let len = buf.len();
+        self.tx_buf[1..=len].copy_from_slice(buf);
+        let mut send_count = if let PayloadSize::Static(_) = self.payload_size {
+            // we have to send `max` bytes, pad the payload with zeroes
+            self.tx_buf[len + 1..=max].fill(0);
+            max
+        } else {
+            len
+        };
+        if self.software_crc_active() {
+            send_count += CRC_LEN;
+            crc::append_crc(&mut self.tx_buf[1..=send_count]);
+        }
+
+        // Add instruction to buffer
+        self.tx_buf[0] = instruction.opcode();
+        // Write to spi
+        self.set_ncs_low()?;
+        let r = self.spi_transfer_tx_buf(send_count)?;
+        let status = Status::from(r[0]);
+        self.set_ncs_high()?;
+
+        Ok(status)
+    }
+
+    /// Returns the CONFIG value for a CRC encoding scheme, in transmission mode and powered down.
+    ///
+    /// The `EN_CRC` bit is set for every encoding scheme, and cleared when the CRC is disabled.
+    fn crc_config(scheme: Option<EncodingScheme>) -> u8 {
+        match scheme {
+            Some(scheme) => (1 << 3) | (scheme.scheme() << 2),
+            None => 0,
+        }
+    }
+
+    /// Updates the CRC bits of the stored CONFIG register, and writes it to the chip.
+    fn set_crc(
+        &mut self,
+        scheme: Option<EncodingScheme>,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.config_reg = (self.config_reg & !Self::crc_config(Some(EncodingScheme::R2Bytes)))
+            | Self::crc_config(scheme);
+        self.write_register(Register::CONFIG, self.config_reg)
+    }
+
+    /// Returns the maximum number of bytes in a single payload.
+    fn max_payload_len(&self) -> usize {
+        let len = match self.payload_size {
+            PayloadSize::Static(n) => n as usize,
+            PayloadSize::Dynamic => MAX_PAYLOAD_SIZE as usize,
+        };
+        if self.software_crc_active() {
+            len.saturating_sub(CRC_LEN)
+        } else {
+            len
+        }
+    }
+
+    /// Returns the maximum number of bytes in a single received payload, taking the payload
+    /// widths of the individual pipes into account.
+    fn max_rx_payload_len(&self) -> usize {
+        match self.payload_size {
+            PayloadSize::Static(_) if self.per_pipe_widths => {
+                let max = self.rx_payload_widths.iter().max().copied().unwrap_or(0) as usize;
+                if self.software_crc_active() {
+                    max.saturating_sub(CRC_LEN)
+                } else {
+                    max
+                }
+            }
+            _ => self.max_payload_len(),
+        }
+    }
+
+    /// Checks that the chip is in transmission mode, so a payload that is loaded gets sent.
+    fn check_tx_mode(&self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        if self.config_reg & 1 != 0 {
+            return Err(TransferError::NotInTxMode);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the software CRC is enabled and the hardware CRC is disabled.
+    fn software_crc_active(&self) -> bool {
+        self.software_crc && self.config_reg & (1 << 3) == 0
+    }
+
+    /// Start transmission:
+    /// pulse CE pin to signal transmission start
+    fn pulse_ce<D: DelayUs<u8>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), TransferError<SPIErr, PinErr>> {
+        self.set_ce_high()?;
+        delay.delay_us(self.ce_pulse_us);
+        self.set_ce_low()
+    }
+
+    /// Loads a payload and pulses CE to transmit it, keeping track of the retry statistics.
+    ///
+    /// Returns the status received while loading the payload.
+    fn start_transmission<D: DelayUs<u8>>(
         &mut self,
-        instruction: Instruction,
+        delay: &mut D,
         buf: &[u8],
     ) -> Result<Status, TransferError<SPIErr, PinErr>> {
-        // Use tx buffer to copy the values into
-        // First byte will be the opcode
-        self.tx_buf[0] = instruction.opcode();
-        self.tx_buf[1..=buf.len()].copy_from_slice(buf);
-        // Write to spi
-        self.set_ncs_low()?;
-        let r = self.spi_transfer_tx_buf(buf.len())?;
-        let status = Status::from(r[0]);
-        self.set_ncs_high()?;
-
+        self.check_tx_mode()?;
+        // The previous packet is done, account for its retransmissions before ARC_CNT resets
+        self.account_retransmissions()?;
+        let status = self.load_payload(Instruction::WTX, buf)?;
+        // The payload is dropped when the TX FIFO was full
+        if status.tx_full() {
+            return Err(TransferError::TxFifoFull);
+        }
+        self.pulse_ce(delay)?;
+        self.tx_packets = self.tx_packets.wrapping_add(1);
+        self.tx_pending = true;
         Ok(status)
     }
 
-    /// Writes values to a given register.
-    ///
-    /// This can be anything that can be turned into a buffer of u8's.
-    /// `IntoBuf` is currently implemented for T and for &[T].
-    /// This means that this function can be polymorphically called for single value writes as well
-    /// as for arrays.
-    fn write_register<T: IntoBuf<u8>>(
-        &mut self,
-        register: Register,
-        buf: T,
-    ) -> Result<(), TransferError<SPIErr, PinErr>> {
-        let buf = buf.into_buf();
-        // Use tx buffer to copy the values into
-        // First byte will be the opcode
-        self.tx_buf[0] = Instruction::WR.opcode() | register.addr();
-        // Copy over the values
-        self.tx_buf[1..=buf.len()].copy_from_slice(buf);
-        // Write to spi
-        self.set_ncs_low()?;
-        self.spi_write_tx_buf(buf.len())?;
-        self.set_ncs_high()?;
-
+    /// Adds the retransmission count of the last packet to the retry statistics, if it hasn't
+    /// been accounted for yet.
+    fn account_retransmissions(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
+        if self.tx_pending {
+            let retransmits = self.observe_tx()?.retransmits();
+            self.tx_retransmits = self.tx_retransmits.wrapping_add(retransmits as u32);
+            self.tx_pending = false;
+        }
         Ok(())
     }
 
-    fn read_register(&mut self, register: Register) -> Result<u8, TransferError<SPIErr, PinErr>> {
-        self.tx_buf[..2].copy_from_slice(&[Instruction::RR.opcode() | register.addr(), 0]);
-        self.set_ncs_low()?;
-        let reg = self.spi_transfer_tx_buf(2)?[1];
-        self.set_ncs_high()?;
-        Ok(reg)
+    /// Polls the status register until the transmission in progress has either been sent or has
+    /// reached the maximum number of retries.
+    ///
+    /// Returns `None` when neither happened within `polls` polls.
+    fn wait_for_transmission<D: DelayUs<u8>>(
+        &mut self,
+        delay: &mut D,
+        polls: u32,
+    ) -> Result<Option<Status>, TransferError<SPIErr, PinErr>> {
+        for _ in 0..polls {
+            let status = self.status()?;
+            if status.data_sent() || status.reached_max_retries() {
+                return Ok(Some(status));
+            }
+            delay.delay_us(Self::TX_POLL_INTERVAL_US);
+        }
+        Ok(None)
     }
 
-    fn read_tx(&mut self) -> Result<[u8; 5], TransferError<SPIErr, PinErr>> {
-        self.tx_buf[0] = Instruction::RR.opcode() | Register::TX_ADDR.addr();
-        self.tx_buf[1..=Self::MAX_ADDR_WIDTH].copy_from_slice(&[0; 5]);
-        // Write to spi
-        self.set_ncs_low()?;
-        let r = self.spi_transfer_tx_buf(Self::MAX_ADDR_WIDTH)?;
-        // Transfer the data read to buf.
-        // Skip first byte because it contains the command.
-        // Make both slices are the same length, otherwise `copy_from_slice` panics.
-        let mut buf = [0; 5];
-        buf.copy_from_slice(&r[1..=5]);
-        self.set_ncs_high()?;
-        Ok(buf)
+    /// Transmits a payload and waits for the outcome.
+    ///
+    /// Returns `true` if the payload was sent, and acknowledged if auto acknowledgement is
+    /// enabled. Payloads that failed are flushed from the TX FIFO.
+    fn transmit_acked<D: DelayUs<u8>>(
+        &mut self,
+        delay: &mut D,
+        buf: &[u8],
+    ) -> Result<bool, TransferError<SPIErr, PinErr>> {
+        self.start_transmission(delay, buf)?;
+        let status = self.wait_for_transmission(delay, Self::TX_POLL_COUNT)?;
+        // The outcome is known, so the retransmissions are final
+        self.account_retransmissions()?;
+        self.reset_status()?;
+        match status {
+            Some(status) if status.data_sent() => Ok(true),
+            _ => {
+                self.flush_tx()?;
+                Ok(false)
+            }
+        }
     }
-    fn read_rx(&mut self) -> Result<[u8; 5], TransferError<SPIErr, PinErr>> {
-        self.tx_buf[0] = Instruction::RR.opcode() | Register::RX_PW_P1.addr();
-        self.tx_buf[1..=Self::MAX_ADDR_WIDTH].copy_from_slice(&[0; 5]);
-        // Write to spi
-        self.set_ncs_low()?;
-        let r = self.spi_transfer_tx_buf(Self::MAX_ADDR_WIDTH)?;
-        // Transfer the data read to buf.
-        // Skip first byte because it contains the command.
-        // Make both slices are the same length, otherwise `copy_from_slice` panics.
-        let mut buf = [0; 5];
-        buf.copy_from_slice(&r[1..=5]);
-        self.set_ncs_high()?;
-        Ok(buf)
+
+    fn from_parts(spi: SPI, ce: CE, ncs: NCS) -> Self {
+        Nrf24l01 {
+            spi,
+            ncs,
+            ce,
+            ce_high: false,
+            config_reg: 0,
+            payload_size: PayloadSize::Static(0),
+            rx_payload_widths: [0; 6],
+            per_pipe_widths: false,
+            addr_width: AddressWidth::default(),
+            data_rate: DataRate::default(),
+            tx_buf: [0; MAX_PAYLOAD_SIZE as usize + 1],
+            spi_retries: 0,
+            ce_pulse_us: MIN_CE_PULSE_US,
+            tx_packets: 0,
+            tx_retransmits: 0,
+            tx_pending: false,
+            last_status: Status::from_raw(Self::STATUS_DEFAULT),
+            software_crc: false,
+            peeked: None,
+            peek_buf: [0; MAX_PAYLOAD_SIZE as usize],
+        }
     }
 
     fn setup_rf(
@@ -921,7 +3779,9 @@ where
         data_rate: DataRate,
         level: PALevel,
     ) -> Result<(), TransferError<SPIErr, PinErr>> {
-        self.write_register(Register::RF_SETUP, data_rate.rate() | level.level())
+        self.write_register(Register::RF_SETUP, data_rate.rate() | level.level())?;
+        self.data_rate = data_rate;
+        Ok(())
     }
 
     fn is_powered_up(&self) -> bool {
@@ -952,52 +3812,77 @@ where
     CE: OutputPin<Error = PinErr>,
 {
     fn set_ce_high<SPIErr>(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
-        self.ce.set_high().map_err(TransferError::Pin)
+        self.ce.set_high().map_err(TransferError::Pin)?;
+        self.ce_high = true;
+        Ok(())
     }
     fn set_ce_low<SPIErr>(&mut self) -> Result<(), TransferError<SPIErr, PinErr>> {
-        self.ce.set_low().map_err(TransferError::Pin)
+        self.ce.set_low().map_err(TransferError::Pin)?;
+        self.ce_high = false;
+        Ok(())
     }
 }
 
 /// Helper function for transfering data over the SPI bus.
 /// Returns the error enum defined in this crate, so the rest of the code can use the
 /// `?` operator.
-impl<SPI, CE, NCS, SPIErr> Nrf24l01<SPI, CE, NCS>
+impl<SPI, CE, NCS, SPIErr, PinErr> Nrf24l01<SPI, CE, NCS>
 where
     SPI: Transfer<u8, Error = SPIErr>,
+    NCS: OutputPin<Error = PinErr>,
 {
     /// *NOTE*
     /// Make sure the data to be transfered is copied to the TX Buf before calling this function.
     /// Because the first byte always has to be the command, the `len` argument
     /// is the inclusive length.
-    fn spi_transfer_tx_buf<PinErr>(
-        &mut self,
-        len: usize,
-    ) -> Result<&[u8], TransferError<SPIErr, PinErr>> {
-        self.spi
-            .transfer(&mut self.tx_buf[..=len])
-            .map_err(TransferError::Spi)
+    ///
+    /// Failed transfers are retried up to `spi_retries` times, restarting the transaction
+    /// by toggling the Chip Select pin.
+    fn spi_transfer_tx_buf(&mut self, len: usize) -> Result<&[u8], TransferError<SPIErr, PinErr>> {
+        // The transfer overwrites the buffer, so keep a copy around for retries
+        let tx_buf = self.tx_buf;
+        let mut retries = self.spi_retries;
+        while let Err(e) = self.spi.transfer(&mut self.tx_buf[..=len]) {
+            if retries == 0 {
+                return Err(TransferError::Spi(e));
+            }
+            retries -= 1;
+            self.tx_buf = tx_buf;
+            self.set_ncs_high()?;
+            self.set_ncs_low()?;
+        }
+        // The chip clocks out the status during the first byte of every transaction
+        self.last_status = Status::from_raw(self.tx_buf[0]);
+        Ok(&self.tx_buf[..=len])
     }
 }
 
 /// Helper function for writing data over the SPI bus.
 /// Returns the error enum defined in this crate, so the rest of the code can use the
 /// `?` operator.
-impl<SPI, CE, NCS, SPIErr> Nrf24l01<SPI, CE, NCS>
+impl<SPI, CE, NCS, SPIErr, PinErr> Nrf24l01<SPI, CE, NCS>
 where
     SPI: Write<u8, Error = SPIErr>,
+    NCS: OutputPin<Error = PinErr>,
 {
     /// *NOTE*
     /// Make sure the data to be written is copied to the TX Buf before calling this function.
     /// Because the first byte always has to be the command, the `len` argument
     /// is the inclusive length.
-    fn spi_write_tx_buf<PinErr>(
-        &mut self,
-        len: usize,
-    ) -> Result<(), TransferError<SPIErr, PinErr>> {
-        self.spi
-            .write(&self.tx_buf[..=len])
-            .map_err(TransferError::Spi)
+    ///
+    /// Failed writes are retried up to `spi_retries` times, restarting the transaction
+    /// by toggling the Chip Select pin.
+    fn spi_write_tx_buf(&mut self, len: usize) -> Result<(), TransferError<SPIErr, PinErr>> {
+        let mut retries = self.spi_retries;
+        while let Err(e) = self.spi.write(&self.tx_buf[..=len]) {
+            if retries == 0 {
+                return Err(TransferError::Spi(e));
+            }
+            retries -= 1;
+            self.set_ncs_high()?;
+            self.set_ncs_low()?;
+        }
+        Ok(())
     }
 }
 
@@ -1052,9 +3937,10 @@ where
     }
 }
 
-/// A trait representing a type that can be turned into a buffer.
+/// A trait representing a type that can be viewed as a buffer.
 ///
 /// Is used for representing single values as well as slices as buffers.
+#[allow(clippy::wrong_self_convention)]
 trait IntoBuf<T> {
     fn into_buf(&self) -> &[T];
 }
@@ -1069,3 +3955,1198 @@ impl<T> IntoBuf<T> for &[T] {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::mock::{DelayMock, PinMock, SpiMock, Transaction};
+    use core::convert::Infallible;
+    use std::vec::Vec;
+
+    // Status value of an idle chip: RX FIFO empty
+    const STATUS: u8 = 0b0000_1110;
+
+    type Chip = Nrf24l01<SpiMock, PinMock, PinMock>;
+
+    fn chip(expectations: &[Transaction]) -> (Chip, SpiMock) {
+        let spi = SpiMock::new(expectations);
+        let chip = Nrf24l01::from_mock(spi.clone(), PinMock::default(), PinMock::default());
+        (chip, spi)
+    }
+
+    /// Initializes a chip with `config` through `new()`, on an SPI bus expecting
+    /// `expectations`.
+    fn init(
+        expectations: &[Transaction],
+        config: NrfConfig,
+    ) -> (Result<Chip, TransferError<Infallible, Infallible>>, SpiMock) {
+        let spi = SpiMock::new(expectations);
+        let chip = Nrf24l01::new(
+            spi.clone(),
+            PinMock::default(),
+            PinMock::default(),
+            &mut DelayMock,
+            config,
+        );
+        (chip, spi)
+    }
+
+    /// SPI transactions of `new()` with the default configuration, up to and including the
+    /// power up, where CONFIG is written with `config` and reads back as `readback`.
+    fn init_transactions(config: u8, readback: u8) -> Vec<Transaction> {
+        std::vec![
+            // Connection check
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b11]),
+            Transaction::write(&[0x23, 0b01]),
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b01]),
+            Transaction::write(&[0x23, 0b11]),
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b11]),
+            Transaction::write(&[0x23, 0b11]),
+            Transaction::write(&[0x24, 0x5f]),
+            Transaction::write(&[0x26, 0x00]),
+            // Only data pipes 0 and 1 are enabled after a reset
+            Transaction::transfer(&[0x02, 0], &[STATUS, 0b11]),
+            Transaction::write(&[0x31, 32]),
+            Transaction::write(&[0x32, 32]),
+            Transaction::write(&[0x23, 0b11]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::write(&[0x25, 76]),
+            Transaction::transfer(&[0xe2], &[STATUS]),
+            Transaction::transfer(&[0xe1], &[STATUS]),
+            Transaction::write(&[0x20, config]),
+            Transaction::transfer(&[0x00, 0], &[STATUS, readback]),
+            Transaction::write(&[0x20, readback | 0b10]),
+        ]
+    }
+
+    fn init_with_crc(crc: Option<EncodingScheme>, config: u8) {
+        let nrf_config = NrfConfig::default().crc_encoding_scheme(crc);
+        let (chip, spi) = init(&init_transactions(config, config), nrf_config);
+        assert!(chip.is_ok());
+        spi.done();
+    }
+
+    #[test]
+    fn new_reports_floating_bus() {
+        // MISO pulled high
+        let (chip, spi) = init(
+            &[
+                Transaction::transfer(&[0x03, 0], &[0xff, 0xff]),
+                Transaction::write(&[0x23, 0b01]),
+                Transaction::transfer(&[0x03, 0], &[0xff, 0xff]),
+                Transaction::write(&[0x23, 0b11]),
+                Transaction::transfer(&[0x03, 0], &[0xff, 0xff]),
+                Transaction::write(&[0x23, 0xff]),
+            ],
+            NrfConfig::default(),
+        );
+        assert!(matches!(chip, Err(TransferError::NotConnected)));
+        spi.done();
+    }
+
+    #[test]
+    fn new_accepts_every_crc_encoding_scheme() {
+        init_with_crc(None, 0b0000_0000);
+        init_with_crc(Some(EncodingScheme::R1Byte), 0b0000_1000);
+        init_with_crc(Some(EncodingScheme::R2Bytes), 0b0000_1100);
+    }
+
+    #[test]
+    fn crc_setters_only_touch_crc_bits() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x20, 0b0000_0010]),
+            Transaction::write(&[0x20, 0b0000_1010]),
+            Transaction::write(&[0x20, 0b0000_1110]),
+        ]);
+        chip.disable_crc().unwrap();
+        chip.enable_crc(EncodingScheme::R1Byte).unwrap();
+        chip.enable_crc(EncodingScheme::R2Bytes).unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn write_rejects_buffer_larger_than_payload() {
+        let (mut chip, spi) = chip(&[]);
+        chip.payload_size = PayloadSize::Static(4);
+        assert!(matches!(
+            chip.write(&mut DelayMock, &[0; 5]),
+            Err(TransferError::BufferTooLarge { max: 4 })
+        ));
+        spi.done();
+    }
+
+    #[test]
+    fn read_rejects_buffer_shorter_than_payload() {
+        let (mut chip, spi) = chip(&[
+            // Dynamic payload of 3 bytes, left in the RX FIFO
+            Transaction::transfer(&[0x60, 0], &[STATUS, 3]),
+            Transaction::transfer(&[0x60, 0], &[STATUS, 3]),
+            Transaction::transfer(&[0x61, 0, 0, 0], &[STATUS, 1, 2, 3]),
+        ]);
+        chip.payload_size = PayloadSize::Static(4);
+        assert!(matches!(
+            chip.read(&mut [0; 3]),
+            Err(TransferError::BufferTooSmall { len: 4 })
+        ));
+        chip.payload_size = PayloadSize::Dynamic;
+        assert!(matches!(
+            chip.read(&mut [0; 2]),
+            Err(TransferError::BufferTooSmall { len: 3 })
+        ));
+        let mut buf = [0; 3];
+        assert_eq!(chip.read(&mut buf).unwrap(), 3);
+        assert_eq!(buf, [1, 2, 3]);
+        spi.done();
+    }
+
+    #[test]
+    fn read_fills_the_payload_length_of_a_larger_buffer() {
+        let (mut chip, spi) = chip(&[Transaction::transfer(
+            &[0x61, 0, 0, 0, 0],
+            &[STATUS, 1, 2, 3, 4],
+        )]);
+        chip.payload_size = PayloadSize::Static(4);
+        let mut buf = [0xff; 5];
+        assert_eq!(chip.read(&mut buf).unwrap(), 4);
+        assert_eq!(buf, [1, 2, 3, 4, 0xff]);
+        spi.done();
+    }
+
+    #[test]
+    fn write_timeout_polls_until_timeout() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0xa0, 1, 2], &[STATUS, 0, 0]),
+            Transaction::transfer(&[0xff], &[STATUS]),
+            Transaction::transfer(&[0xff], &[STATUS]),
+        ]);
+        chip.payload_size = PayloadSize::Static(2);
+        assert!(matches!(
+            chip.write_timeout(&mut DelayMock, &[1, 2], 150),
+            Err(TransferError::Timeout)
+        ));
+        spi.done();
+    }
+
+    #[test]
+    fn write_timeout_reports_max_retries() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0xa0, 1, 2], &[STATUS, 0, 0]),
+            Transaction::transfer(&[0xff], &[STATUS | 0b0001_0000]),
+            Transaction::transfer(&[0x08, 0], &[STATUS, 0x0f]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::transfer(&[0b1110_0001], &[STATUS]),
+        ]);
+        chip.payload_size = PayloadSize::Static(2);
+        assert!(matches!(
+            chip.write_timeout(&mut DelayMock, &[1, 2], 1000),
+            Err(TransferError::MaximumRetries)
+        ));
+        assert_eq!(chip.retry_stats(), (1, 15));
+        spi.done();
+    }
+
+    #[test]
+    fn write_timeout_tells_ack_from_no_ack() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0xa0, 1, 2], &[STATUS, 0, 0]),
+            Transaction::transfer(&[0xff], &[STATUS | 0b0010_0000]),
+            Transaction::transfer(&[0x08, 0], &[STATUS, 0x01]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::transfer(&[0x01, 0], &[STATUS, 0b0011_1111]),
+            // Auto acknowledgement disabled
+            Transaction::transfer(&[0xa0, 1, 2], &[STATUS, 0, 0]),
+            Transaction::transfer(&[0xff], &[STATUS | 0b0010_0000]),
+            Transaction::transfer(&[0x08, 0], &[STATUS, 0x00]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::transfer(&[0x01, 0], &[STATUS, 0]),
+        ]);
+        chip.payload_size = PayloadSize::Static(2);
+        assert_eq!(
+            chip.write_timeout(&mut DelayMock, &[1, 2], 1000).unwrap(),
+            WriteOutcome::Acknowledged
+        );
+        assert_eq!(
+            chip.write_timeout(&mut DelayMock, &[1, 2], 1000).unwrap(),
+            WriteOutcome::SentNoAck
+        );
+        spi.done();
+    }
+
+    #[test]
+    fn wake_restores_listening() {
+        let spi = SpiMock::new(&[
+            Transaction::write(&[0x20, 0b0000_1111]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::transfer(&[0b1110_0001], &[STATUS]),
+            Transaction::transfer(&[0b1110_0010], &[STATUS]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::write(&[0x20, 0b0000_1101]),
+            Transaction::write(&[0x20, 0b0000_1111]),
+        ]);
+        let ce = PinMock::default();
+        let mut chip = Nrf24l01::from_mock(spi.clone(), ce.clone(), PinMock::default());
+        chip.start_listening().unwrap();
+        assert!(chip.is_listening());
+        chip.sleep().unwrap();
+        assert!(!ce.is_high());
+        assert!(!chip.is_listening());
+        chip.wake(&mut DelayMock).unwrap();
+        assert!(ce.is_high());
+        assert!(chip.is_listening());
+        spi.done();
+    }
+
+    #[test]
+    fn data_rate_rejects_reserved_bits() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x06, 0], &[STATUS, 0b0000_1110]),
+            Transaction::transfer(&[0x06, 0], &[STATUS, 0xff]),
+        ]);
+        assert_eq!(chip.data_rate().unwrap(), DataRate::R2Mbps);
+        assert!(matches!(
+            chip.data_rate(),
+            Err(TransferError::InvalidRegisterValue(0xff))
+        ));
+        spi.done();
+    }
+
+    #[test]
+    fn data_rate_rejects_both_rate_bits() {
+        let (mut chip, spi) = chip(&[Transaction::transfer(&[0x06, 0], &[STATUS, 0b0010_1000])]);
+        assert!(matches!(
+            chip.data_rate(),
+            Err(TransferError::InvalidRegisterValue(0b0010_1000))
+        ));
+        spi.done();
+    }
+
+    #[test]
+    fn address_width_rejects_illegal_value() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b10]),
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b00]),
+        ]);
+        assert_eq!(chip.address_width().unwrap(), AddressWidth::R4Bytes);
+        assert!(matches!(
+            chip.address_width(),
+            Err(TransferError::InvalidRegisterValue(0))
+        ));
+        spi.done();
+    }
+
+    #[test]
+    fn write_retry_resends_after_max_retries() {
+        let (mut chip, spi) = chip(&[
+            // First attempt reaches the maximum number of retries
+            Transaction::transfer(&[0xa0, 1, 2], &[STATUS, 0, 0]),
+            Transaction::transfer(&[0xff], &[STATUS | 0b0001_0000]),
+            Transaction::transfer(&[0x08, 0], &[STATUS, 0x1f]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::transfer(&[0b1110_0001], &[STATUS]),
+            // Reset the lost packets counter
+            Transaction::transfer(&[0x05, 0], &[STATUS, 76]),
+            Transaction::write(&[0x25, 76]),
+            // Second attempt is acknowledged
+            Transaction::transfer(&[0xa0, 1, 2], &[STATUS, 0, 0]),
+            Transaction::transfer(&[0xff], &[STATUS | 0b0010_0000]),
+            Transaction::transfer(&[0x08, 0], &[STATUS, 0x02]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+        ]);
+        chip.payload_size = PayloadSize::Static(2);
+        assert_eq!(chip.write_retry(&mut DelayMock, &[1, 2], 3).unwrap(), 2);
+        assert_eq!(chip.retry_stats(), (2, 17));
+        spi.done();
+    }
+
+    #[test]
+    fn write_all_keeps_tx_fifo_filled() {
+        let sent = |arc| {
+            std::vec![
+                Transaction::transfer(&[0xff], &[STATUS | 0b0010_0000]),
+                Transaction::transfer(&[0x08, 0], &[STATUS, arc]),
+                Transaction::write(&[0x27, 0b0010_0000]),
+            ]
+        };
+        let mut expectations = std::vec![
+            // The TX FIFO is filled before CE goes high
+            Transaction::transfer(&[0xa0, 1, 2], &[STATUS, 0, 0]),
+            Transaction::transfer(&[0xa0, 3, 4], &[STATUS, 0, 0]),
+            Transaction::transfer(&[0xa0, 5, 6], &[STATUS, 0, 0]),
+        ];
+        // Every payload that is sent makes room for the next one
+        expectations.extend(sent(0x00));
+        expectations.push(Transaction::transfer(&[0xa0, 7, 8], &[STATUS, 0, 0]));
+        expectations.extend(sent(0x01));
+        expectations.push(Transaction::transfer(&[0xa0, 9, 0], &[STATUS, 0, 0]));
+        expectations.extend(sent(0x00));
+        // The fourth payload reaches the maximum number of retries
+        expectations.extend(std::vec![
+            Transaction::transfer(&[0xff], &[STATUS | 0b0001_0000]),
+            Transaction::transfer(&[0x08, 0], &[STATUS, 0x0f]),
+            Transaction::write(&[0x27, 0b0001_0000]),
+            Transaction::transfer(&[0b1110_0001], &[STATUS]),
+        ]);
+        let (mut chip, spi) = chip(&expectations);
+        let ce = PinMock::default();
+        chip.ce = ce.clone();
+        chip.payload_size = PayloadSize::Static(2);
+        assert_eq!(
+            chip.write_all(&mut DelayMock, &[1, 2, 3, 4, 5, 6, 7, 8, 9])
+                .unwrap(),
+            6
+        );
+        assert!(!ce.is_high());
+        assert_eq!(chip.retry_stats(), (4, 16));
+        spi.done();
+    }
+
+    #[test]
+    fn measure_success_rate_enables_auto_ack() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x01, 0], &[STATUS, 0b10]),
+            Transaction::write(&[0x21, 0b11]),
+            // Acknowledged
+            Transaction::transfer(&[0xa0, 1], &[STATUS, 0]),
+            Transaction::transfer(&[0xff], &[STATUS | 0b0010_0000]),
+            Transaction::transfer(&[0x08, 0], &[STATUS, 0x00]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            // Reaches the maximum number of retries
+            Transaction::transfer(&[0xa0, 1], &[STATUS, 0]),
+            Transaction::transfer(&[0xff], &[STATUS | 0b0001_0000]),
+            Transaction::transfer(&[0x08, 0], &[STATUS, 0x0f]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::transfer(&[0b1110_0001], &[STATUS]),
+            // EN_AA is restored
+            Transaction::write(&[0x21, 0b10]),
+        ]);
+        chip.payload_size = PayloadSize::Static(1);
+        assert_eq!(
+            chip.measure_success_rate(&mut DelayMock, 2, &[1]).unwrap(),
+            50
+        );
+        spi.done();
+    }
+
+    #[test]
+    fn probe_data_rates_restores_rf_setup_on_error() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x06, 0], &[STATUS, 0b0010_0110]),
+            // 2 Mbps at the original power level
+            Transaction::write(&[0x26, 0b0000_1110]),
+            // The payload is rejected, the original setup is written back
+            Transaction::write(&[0x26, 0b0010_0110]),
+        ]);
+        chip.payload_size = PayloadSize::Static(1);
+        assert!(matches!(
+            chip.probe_data_rates(&mut DelayMock, &[1, 2]),
+            Err(TransferError::BufferTooLarge { max: 1 })
+        ));
+        assert_eq!(chip.data_rate, DataRate::R250kbps);
+        spi.done();
+    }
+
+    #[test]
+    fn scan_channels_counts_detected_signals() {
+        let mut expectations = std::vec![Transaction::transfer(&[0x05, 0], &[STATUS, 76])];
+        for channel in 0..=MAX_CHANNEL {
+            expectations.push(Transaction::write(&[0x25, channel]));
+            for sample in 0..2 {
+                let rpd = match channel {
+                    5 => 1,
+                    80 if sample == 1 => 1,
+                    _ => 0,
+                };
+                expectations.extend(std::vec![
+                    Transaction::write(&[0x20, 0b0000_1111]),
+                    Transaction::write(&[0x27, 0b0111_0000]),
+                    Transaction::write(&[0x20, 0b0000_1110]),
+                    Transaction::transfer(&[0x09, 0], &[STATUS, rpd]),
+                ]);
+            }
+        }
+        // The original channel is restored
+        expectations.push(Transaction::write(&[0x25, 76]));
+        expectations.push(Transaction::write(&[0x27, 0b0111_0000]));
+        let (mut chip, spi) = chip(&expectations);
+        let hits = chip.scan_channels(&mut DelayMock, 2).unwrap();
+        for (channel, hits) in hits.iter().enumerate() {
+            let expected = match channel {
+                5 => 2,
+                80 => 1,
+                _ => 0,
+            };
+            assert_eq!(*hits, expected, "channel {}", channel);
+        }
+        assert!(!chip.is_listening());
+        spi.done();
+    }
+
+    #[test]
+    fn write_all_rejects_zero_payload_size() {
+        let (mut chip, spi) = chip(&[]);
+        chip.payload_size = PayloadSize::Static(0);
+        assert!(matches!(
+            chip.write_all(&mut DelayMock, &[1, 2, 3]),
+            Err(TransferError::BufferTooLarge { max: 0 })
+        ));
+        assert_eq!(chip.write_all(&mut DelayMock, &[]).unwrap(), 0);
+        spi.done();
+    }
+
+    #[test]
+    fn per_pipe_payload_widths_are_used_for_reads() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x33, 8]),
+            Transaction::transfer(&[0xff], &[0b0000_0100]),
+            Transaction::transfer(
+                &[0x61, 0, 0, 0, 0, 0, 0, 0, 0],
+                &[0b0000_0100, 1, 2, 3, 4, 5, 6, 7, 8],
+            ),
+            Transaction::transfer(&[0xff], &[0b0000_0010]),
+            Transaction::transfer(&[0x61, 0, 0, 0, 0], &[0b0000_0010, 1, 2, 3, 4]),
+        ]);
+        chip.payload_size = PayloadSize::Static(4);
+        chip.rx_payload_widths = [4; 6];
+        chip.set_payload_size_pipe(DataPipe::DP2, 8).unwrap();
+        let mut buf = [0; 8];
+        assert_eq!(
+            chip.read_with_pipe(&mut buf).unwrap(),
+            Some((DataPipe::DP2, 8))
+        );
+        assert_eq!(buf, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            chip.read_with_pipe(&mut buf).unwrap(),
+            Some((DataPipe::DP1, 4))
+        );
+        spi.done();
+    }
+
+    #[test]
+    fn software_crc_is_added_and_checked() {
+        let (mut chip, spi) = chip(&[
+            // W_TX_PAYLOAD with the CRC appended, the previous payload was sent
+            Transaction::transfer(
+                &[0xa0, 1, 2, 0x0e, 0x7c],
+                &[STATUS | 0b0010_0000, 0, 0, 0, 0],
+            ),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::transfer(&[0x61, 0, 0, 0, 0], &[STATUS, 1, 2, 0x0e, 0x7c]),
+            Transaction::transfer(&[0x61, 0, 0, 0, 0], &[STATUS, 1, 3, 0x0e, 0x7c]),
+        ]);
+        chip.payload_size = PayloadSize::Static(4);
+        chip.config_reg = 0b0000_0010;
+        chip.set_software_crc(true);
+        // The CRC takes up 2 bytes of the payload
+        assert!(matches!(
+            chip.write(&mut DelayMock, &[1, 2, 3, 4]),
+            Err(TransferError::BufferTooLarge { max: 2 })
+        ));
+        chip.write(&mut DelayMock, &[1, 2]).unwrap();
+        let mut buf = [0; 2];
+        assert_eq!(chip.read(&mut buf).unwrap(), 2);
+        assert_eq!(buf, [1, 2]);
+        assert!(matches!(
+            chip.read(&mut buf),
+            Err(TransferError::CrcMismatch)
+        ));
+        spi.done();
+    }
+
+    #[test]
+    #[cfg(feature = "payload")]
+    fn write_payload_checks_static_size() {
+        let (mut chip, spi) = chip(&[
+            // W_TX_PAYLOAD, the previous payload was sent
+            Transaction::transfer(&[0xa0, 1, 2], &[STATUS | 0b0010_0000, 0, 0]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+        ]);
+        chip.payload_size = PayloadSize::Static(2);
+        assert!(matches!(
+            chip.write_payload(&mut DelayMock, &Payload::new([1, 2, 3])),
+            Err(TransferError::PayloadSizeMismatch {
+                expected: 2,
+                got: 3
+            })
+        ));
+        chip.write_payload(&mut DelayMock, &Payload::new([1, 2]))
+            .unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn air_time_follows_the_configuration() {
+        let (mut chip, spi) = chip(&[]);
+        // 5 byte address, 2 byte CRC and a static payload of 32 bytes at 1 Mbps
+        assert_eq!(chip.air_time_us(4), 329);
+        chip.payload_size = PayloadSize::Dynamic;
+        chip.addr_width = AddressWidth::R3Bytes;
+        chip.config_reg = 0b0000_1010;
+        chip.data_rate = DataRate::R2Mbps;
+        // (8 * (1 + 3 + 4 + 1) + 9) / 2 = 40.5
+        assert_eq!(chip.air_time_us(4), 41);
+        spi.done();
+    }
+
+    #[test]
+    fn rx_overflow_needs_full_fifo_and_data_ready() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x17, 0], &[0b0100_0010, 0b0001_0010]),
+            Transaction::transfer(&[0x17, 0], &[0b0000_0010, 0b0001_0010]),
+            Transaction::transfer(&[0x17, 0], &[0b0100_0010, 0b0001_0000]),
+        ]);
+        assert!(chip.rx_overflow().unwrap());
+        assert!(!chip.rx_overflow().unwrap());
+        assert!(!chip.rx_overflow().unwrap());
+        spi.done();
+    }
+
+    #[test]
+    fn release_powers_down_and_returns_peripherals() {
+        let spi = SpiMock::new(&[Transaction::write(&[0x20, 0b0000_1100])]);
+        let ce = PinMock::default();
+        let mut chip = Nrf24l01::from_mock(spi.clone(), ce.clone(), PinMock::default());
+        chip.set_ce_high::<Infallible>().unwrap();
+        let (spi_back, ce_back, _) = chip.release().unwrap();
+        assert!(!ce_back.is_high());
+        spi_back.done();
+        spi.done();
+    }
+
+    #[test]
+    fn write_requires_tx_mode() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x20, 0b0000_1111]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+        ]);
+        chip.start_listening().unwrap();
+        assert!(matches!(
+            chip.write(&mut DelayMock, &[1, 2]),
+            Err(TransferError::NotInTxMode)
+        ));
+        assert!(matches!(
+            chip.write_fast(&[1, 2]),
+            Err(TransferError::NotInTxMode)
+        ));
+        // Still listening
+        assert!(chip.is_listening());
+        spi.done();
+    }
+
+    #[test]
+    fn write_reports_full_tx_fifo() {
+        let (mut chip, spi) = chip(&[Transaction::transfer(&[0xa0, 1, 2], &[STATUS | 1, 0, 0])]);
+        chip.payload_size = PayloadSize::Static(2);
+        assert!(matches!(
+            chip.write(&mut DelayMock, &[1, 2]),
+            Err(TransferError::TxFifoFull)
+        ));
+        assert_eq!(chip.retry_stats(), (0, 0));
+        spi.done();
+    }
+
+    #[test]
+    fn set_data_rate_and_pa_level_keep_other_bits() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x06, 0], &[STATUS, 0b0000_1111]),
+            Transaction::write(&[0x26, 0b0010_0111]),
+            Transaction::transfer(&[0x06, 0], &[STATUS, 0b0010_0111]),
+            Transaction::write(&[0x26, 0b0010_0011]),
+        ]);
+        chip.set_data_rate(DataRate::R250kbps).unwrap();
+        chip.set_pa_level(PALevel::Low).unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn set_lna_gain_keeps_rate_and_pa_bits() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x06, 0], &[STATUS, 0b0000_1110]),
+            Transaction::write(&[0x26, 0b0000_1111]),
+            Transaction::transfer(&[0x06, 0], &[STATUS, 0b0000_1111]),
+            Transaction::write(&[0x26, 0b0000_1110]),
+        ]);
+        chip.set_lna_gain(true).unwrap();
+        chip.set_lna_gain(false).unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn set_static_payload_size_disables_ack_payloads() {
+        let (mut chip, spi) = chip(&[
+            // EN_DPL and EN_ACK_PAY are both cleared, EN_DYN_ACK is kept
+            Transaction::transfer(&[0x1d, 0], &[STATUS, 0b111]),
+            Transaction::write(&[0x3d, 0b001]),
+            Transaction::write(&[0x3c, 0]),
+            Transaction::transfer(&[0x02, 0], &[STATUS, 0b11]),
+            Transaction::write(&[0x31, 8]),
+            Transaction::write(&[0x32, 8]),
+        ]);
+        chip.payload_size = PayloadSize::Dynamic;
+        chip.set_payload_size(8).unwrap();
+        assert_eq!(chip.payload_size(), PayloadSize::Static(8));
+        spi.done();
+    }
+
+    #[test]
+    fn set_dynamic_payload_pipe_tracks_en_dpl() {
+        let (mut chip, spi) = chip(&[
+            // Enable pipe 1, EN_DPL gets set
+            Transaction::transfer(&[0x1c, 0], &[STATUS, 0]),
+            Transaction::transfer(&[0x1d, 0], &[STATUS, 0]),
+            Transaction::write(&[0x3d, 0b100]),
+            Transaction::write(&[0x3c, 0b10]),
+            // Disable the last dynamic pipe, EN_DPL gets cleared
+            Transaction::transfer(&[0x1c, 0], &[STATUS, 0b10]),
+            Transaction::transfer(&[0x1d, 0], &[STATUS, 0b100]),
+            Transaction::write(&[0x3d, 0]),
+            Transaction::write(&[0x3c, 0]),
+        ]);
+        chip.set_dynamic_payload_pipe(DataPipe::DP1, true).unwrap();
+        chip.set_dynamic_payload_pipe(DataPipe::DP1, false).unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn set_dynamic_payload_pipe_keeps_ack_payloads_consistent() {
+        let (mut chip, spi) = chip(&[
+            // Disable pipe 1, ack payloads still have pipe 0
+            Transaction::transfer(&[0x1c, 0], &[STATUS, 0b11]),
+            Transaction::transfer(&[0x1d, 0], &[STATUS, 0b110]),
+            Transaction::write(&[0x3d, 0b110]),
+            Transaction::write(&[0x3c, 0b01]),
+            // Disable pipe 0, EN_ACK_PAY and EN_DPL get cleared
+            Transaction::transfer(&[0x1c, 0], &[STATUS, 0b01]),
+            Transaction::transfer(&[0x1d, 0], &[STATUS, 0b110]),
+            Transaction::write(&[0x3d, 0]),
+            Transaction::write(&[0x3c, 0]),
+        ]);
+        chip.set_dynamic_payload_pipe(DataPipe::DP1, false).unwrap();
+        chip.set_dynamic_payload_pipe(DataPipe::DP0, false).unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn read_flushes_corrupt_dynamic_payload() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x60, 0], &[STATUS, 3]),
+            Transaction::transfer(&[0x61, 0, 0, 0], &[0b0000_0010, 1, 2, 3]),
+            Transaction::transfer(&[0x60, 0], &[STATUS, 33]),
+            Transaction::transfer(&[0b1110_0010], &[STATUS]),
+        ]);
+        chip.payload_size = PayloadSize::Dynamic;
+        let mut buf = [0; 32];
+        assert_eq!(chip.read(&mut buf).unwrap(), 3);
+        assert_eq!(buf[..3], [1, 2, 3]);
+        assert!(matches!(
+            chip.read(&mut buf),
+            Err(TransferError::CorruptPayload)
+        ));
+        spi.done();
+    }
+
+    #[test]
+    fn read_blocking_polls_until_data_or_timeout() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0xff], &[STATUS]),
+            Transaction::transfer(&[0xff], &[0b0000_0010]),
+            Transaction::transfer(&[0x61, 0, 0], &[0b0000_0010, 1, 2]),
+            Transaction::transfer(&[0xff], &[STATUS]),
+            Transaction::transfer(&[0xff], &[STATUS]),
+        ]);
+        chip.payload_size = PayloadSize::Static(2);
+        let mut buf = [0; 2];
+        assert_eq!(chip.read_blocking(&mut DelayMock, &mut buf, 10).unwrap(), 2);
+        assert_eq!(buf, [1, 2]);
+        assert!(matches!(
+            chip.read_blocking(&mut DelayMock, &mut buf, 1),
+            Err(TransferError::Timeout)
+        ));
+        spi.done();
+    }
+
+    #[test]
+    fn read_all_drains_rx_fifo() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x17, 0], &[STATUS, 0]),
+            Transaction::transfer(&[0x61, 0, 0], &[0b0000_0010, 1, 2]),
+            Transaction::transfer(&[0x17, 0], &[STATUS, 0]),
+            Transaction::transfer(&[0x61, 0, 0], &[0b0000_0100, 3, 4]),
+            Transaction::transfer(&[0x17, 0], &[STATUS, 1]),
+        ]);
+        chip.payload_size = PayloadSize::Static(2);
+        let mut received = Vec::new();
+        let count = chip
+            .read_all(|pipe, data| received.push((pipe, [data[0], data[1]])))
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(received, [(DataPipe::DP1, [1, 2]), (DataPipe::DP2, [3, 4])]);
+        spi.done();
+    }
+
+    #[test]
+    fn reset_flushes_clears_and_rewrites_config() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0b1110_0001], &[STATUS]),
+            Transaction::transfer(&[0b1110_0010], &[STATUS]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::transfer(&[0x05, 0], &[STATUS, 76]),
+            Transaction::write(&[0x25, 76]),
+            Transaction::write(&[0x20, 0b0000_1110]),
+        ]);
+        chip.reset(&mut DelayMock).unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn new_masks_interrupts_in_first_config_write() {
+        let config = 0b0111_1100;
+        let nrf_config = NrfConfig::default().masked_interrupts(Interrupts::all());
+        let (chip, spi) = init(&init_transactions(config, config), nrf_config);
+        assert!(chip.is_ok());
+        spi.done();
+    }
+
+    #[test]
+    fn new_reports_config_mismatch() {
+        // MISO stuck low
+        let mut transactions = init_transactions(0b0000_1100, 0);
+        transactions.push(Transaction::transfer(&[0x03, 0], &[STATUS, 0]));
+        let (chip, spi) = init(&transactions, NrfConfig::default());
+        match chip {
+            Err(TransferError::CommunicationError(mismatch)) => {
+                assert_eq!(mismatch.expected(), 0b0000_1110);
+                assert_eq!(mismatch.actual(), 0b0000_0010);
+                assert_eq!(mismatch.setup_aw(), 0);
+            }
+            _ => panic!("expected a communication error"),
+        }
+        spi.done();
+    }
+
+    #[test]
+    fn read_register_transfers_opcode_and_one_byte() {
+        let (mut chip, spi) = chip(&[Transaction::transfer(&[0x05, 0], &[STATUS, 76])]);
+        assert_eq!(chip.read_register(Register::RF_CH).unwrap(), 76);
+        spi.done();
+    }
+
+    #[test]
+    fn read_register_ignores_stale_transmit_buffer() {
+        // Register reads used to clock out a third byte, taken from whatever the previous
+        // transaction left in the transmit buffer
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0xa0, 1, 2], &[STATUS | 0b0010_0000, 0, 0]),
+            Transaction::write(&[0x27, 0b0111_0000]),
+            Transaction::transfer(&[0x05, 0], &[STATUS, 76]),
+        ]);
+        chip.payload_size = PayloadSize::Static(2);
+        chip.write(&mut DelayMock, &[1, 2]).unwrap();
+        assert_eq!(chip.read_register(Register::RF_CH).unwrap(), 76);
+        spi.done();
+    }
+
+    #[test]
+    fn read_register_bytes_reads_full_address() {
+        let (mut chip, spi) = chip(&[Transaction::transfer(
+            &[0x10, 0, 0, 0, 0, 0],
+            &[STATUS, b'N', b'o', b'd', b'e', b'1'],
+        )]);
+        let mut addr = [0; 5];
+        chip.read_register_bytes(Register::TX_ADDR, &mut addr)
+            .unwrap();
+        assert_eq!(&addr, b"Node1");
+        spi.done();
+    }
+
+    #[test]
+    fn raw_register_access_masks_address() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x08, 0], &[STATUS, 0x12]),
+            Transaction::write(&[0x3c, 0b10]),
+        ]);
+        assert_eq!(chip.read_register_raw(0x08).unwrap(), 0x12);
+        chip.write_register_raw(0xfc, 0b10).unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn write_register_writes_opcode_and_value() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x25, 10]),
+            Transaction::write(&[0x30, 1, 2, 3, 4, 5]),
+        ]);
+        chip.write_register(Register::RF_CH, 10).unwrap();
+        chip.write_register(Register::TX_ADDR, &[1, 2, 3, 4, 5][..])
+            .unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn send_command_bytes_returns_status() {
+        let (mut chip, spi) = chip(&[Transaction::transfer(&[0b1010_0000, 1, 2], &[STATUS, 0, 0])]);
+        let status = chip.send_command_bytes(Instruction::WTX, &[1, 2]).unwrap();
+        assert_eq!(status.value(), STATUS);
+        spi.done();
+    }
+
+    #[test]
+    fn last_status_is_cached_on_transfer() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0xff], &[0b0010_1110]),
+            Transaction::transfer(&[0x06, 0], &[0b0100_0010, 0]),
+        ]);
+        assert_eq!(chip.last_status(), Status::from_raw(STATUS));
+        chip.status().unwrap();
+        assert!(chip.last_status().data_sent());
+        chip.read_register(Register::RF_SETUP).unwrap();
+        assert_eq!(chip.last_status().value(), 0b0100_0010);
+        spi.done();
+    }
+
+    #[test]
+    fn ncs_is_released_after_transaction() {
+        let spi = SpiMock::new(&[Transaction::transfer(&[0xff], &[STATUS])]);
+        let ncs = PinMock::default();
+        let mut chip = Nrf24l01::from_mock(spi.clone(), PinMock::default(), ncs.clone());
+        chip.status().unwrap();
+        assert!(ncs.is_high());
+        spi.done();
+    }
+
+    #[test]
+    fn set_channel_clamps_to_max_channel() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x25, 76]),
+            Transaction::write(&[0x25, 125]),
+        ]);
+        chip.set_channel(76).unwrap();
+        chip.set_channel(200).unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn open_reading_pipe_writes_full_address_for_pipe_1() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x2b, b'N', b'o', b'd', b'e', b'1']),
+            Transaction::transfer(&[0x02, 0], &[STATUS, 0b0000_0011]),
+            Transaction::write(&[0x22, 0b0000_0011]),
+        ]);
+        chip.open_reading_pipe(DataPipe::DP1, b"Node1").unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn open_reading_pipe_writes_lsb_for_pipe_2() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x2c, b'N']),
+            Transaction::transfer(&[0x02, 0], &[STATUS, 0b0000_0011]),
+            // The pipe is newly enabled, so its payload width is written
+            Transaction::write(&[0x33, 32]),
+            Transaction::write(&[0x22, 0b0000_0111]),
+        ]);
+        chip.open_reading_pipe(DataPipe::DP2, b"Node2").unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn configure_pipe_group_sets_base_before_lsbs() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x2b, b'1', b'N', b'o', b'd', b'e']),
+            Transaction::transfer(&[0x02, 0], &[STATUS, 0b0000_0011]),
+            Transaction::write(&[0x22, 0b0000_0011]),
+            Transaction::write(&[0x2c, b'2']),
+            Transaction::transfer(&[0x02, 0], &[STATUS, 0b0000_0011]),
+            Transaction::write(&[0x33, 32]),
+            Transaction::write(&[0x22, 0b0000_0111]),
+        ]);
+        assert!(matches!(
+            chip.configure_pipe_group(b"1Node", b"21"),
+            Err(TransferError::Config(ConfigError::DuplicateAddress(b'1')))
+        ));
+        assert!(matches!(
+            chip.configure_pipe_group(b"1Node", b"23456"),
+            Err(TransferError::Config(ConfigError::TooManyPipes(5)))
+        ));
+        chip.configure_pipe_group(b"1Node", b"2").unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn set_rx_address_rejects_empty_address() {
+        let (mut chip, spi) = chip(&[]);
+        assert!(matches!(
+            chip.set_rx_address(DataPipe::DP3, &[]),
+            Err(TransferError::InvalidAddressLength {
+                expected: 5,
+                got: 0
+            })
+        ));
+        spi.done();
+    }
+
+    #[test]
+    fn set_frequency_mhz_validates_range() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x25, 125]),
+            Transaction::transfer(&[0x05, 0], &[STATUS, 125]),
+        ]);
+        assert!(matches!(
+            chip.set_frequency_mhz(2526),
+            Err(TransferError::Config(ConfigError::FrequencyOutOfRange(
+                2526
+            )))
+        ));
+        chip.set_frequency_mhz(2525).unwrap();
+        assert_eq!(chip.frequency_mhz().unwrap(), 2525);
+        spi.done();
+    }
+
+    #[test]
+    fn open_writing_pipe_respects_address_width() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x23, 0b01]),
+            Transaction::write(&[0x2a, b'a', b'b', b'c']),
+            Transaction::write(&[0x30, b'a', b'b', b'c']),
+        ]);
+        chip.set_address_width(3).unwrap();
+        assert!(matches!(
+            chip.open_writing_pipe(b"Node1"),
+            Err(TransferError::InvalidAddressLength {
+                expected: 3,
+                got: 5
+            })
+        ));
+        assert!(matches!(
+            chip.open_writing_pipe(b"ab"),
+            Err(TransferError::InvalidAddressLength {
+                expected: 3,
+                got: 2
+            })
+        ));
+        chip.open_writing_pipe(b"abc").unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn set_rx_address_rejects_short_address() {
+        let (mut chip, spi) = chip(&[Transaction::write(&[0x2d, b'3'])]);
+        assert!(matches!(
+            chip.set_rx_address(DataPipe::DP1, b"1No"),
+            Err(TransferError::InvalidAddressLength {
+                expected: 5,
+                got: 3
+            })
+        ));
+        assert!(matches!(
+            chip.set_rx_address(DataPipe::DP3, b"3No"),
+            Err(TransferError::InvalidAddressLength {
+                expected: 5,
+                got: 3
+            })
+        ));
+        chip.set_rx_address(DataPipe::DP3, b"3").unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn close_reading_pipe_clears_en_rxaddr_bit() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x02, 0], &[STATUS, 0b0000_0111]),
+            Transaction::write(&[0x22, 0b0000_0011]),
+        ]);
+        chip.close_reading_pipe(DataPipe::DP2).unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn read_with_pipe_latches_pipe_from_payload_read() {
+        let (mut chip, spi) = chip(&[
+            // Payload on pipe 2
+            Transaction::transfer(&[0x61, 0, 0, 0, 0], &[0b0000_0100, 1, 2, 3, 4]),
+            // RX FIFO empty
+            Transaction::transfer(&[0x61, 0, 0, 0, 0], &[STATUS, 0, 0, 0, 0]),
+        ]);
+        chip.payload_size = PayloadSize::Static(4);
+        let mut buf = [0; 4];
+        assert_eq!(
+            chip.read_with_pipe(&mut buf).unwrap(),
+            Some((DataPipe::DP2, 4))
+        );
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert_eq!(chip.read_with_pipe(&mut buf).unwrap(), None);
+        spi.done();
+    }
+
+    #[test]
+    fn config_register_decodes_bits() {
+        let (mut chip, spi) = chip(&[Transaction::transfer(&[0x00, 0], &[STATUS, 0b0100_1011])]);
+        let config = chip.config_register().unwrap();
+        assert!(config.powered_up());
+        assert!(config.is_rx());
+        assert!(config.crc_enabled());
+        assert_eq!(config.masked_interrupts(), Interrupts::new().data_ready());
+        spi.done();
+    }
+
+    #[test]
+    fn is_connected_toggles_setup_aw() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b11]),
+            Transaction::write(&[0x23, 0b01]),
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b01]),
+            Transaction::write(&[0x23, 0b11]),
+            Transaction::transfer(&[0x03, 0], &[STATUS, 0b11]),
+            Transaction::write(&[0x23, 0b11]),
+        ]);
+        assert!(chip.is_connected().unwrap());
+        spi.done();
+    }
+
+    #[test]
+    fn is_connected_rejects_miso_stuck_high() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x03, 0], &[0xff, 0xff]),
+            Transaction::write(&[0x23, 0b01]),
+            Transaction::transfer(&[0x03, 0], &[0xff, 0xff]),
+            Transaction::write(&[0x23, 0b11]),
+            Transaction::transfer(&[0x03, 0], &[0xff, 0xff]),
+            Transaction::write(&[0x23, 0xff]),
+        ]);
+        assert!(!chip.is_connected().unwrap());
+        spi.done();
+    }
+
+    #[test]
+    fn verify_config_detects_ack_payloads_without_auto_ack() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x1d, 0], &[STATUS, 0b110]),
+            Transaction::transfer(&[0x1c, 0], &[STATUS, 0b0011_1111]),
+            Transaction::transfer(&[0x01, 0], &[STATUS, 0]),
+            Transaction::transfer(&[0x00, 0], &[STATUS, 0b0000_1110]),
+            Transaction::transfer(&[0x1d, 0], &[STATUS, 0b110]),
+            Transaction::transfer(&[0x1c, 0], &[STATUS, 0b0011_1111]),
+            Transaction::transfer(&[0x01, 0], &[STATUS, 0b0011_1111]),
+            Transaction::transfer(&[0x00, 0], &[STATUS, 0b0000_1110]),
+        ]);
+        assert!(matches!(
+            chip.verify_config(),
+            Err(TransferError::InconsistentConfig(
+                Inconsistency::AckPayloadsWithoutAutoAck
+            ))
+        ));
+        chip.verify_config().unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn link_quality_bundles_diagnostics() {
+        let (mut chip, spi) = chip(&[
+            Transaction::transfer(&[0x08, 0], &[STATUS, 0x23]),
+            Transaction::transfer(&[0x09, 0], &[STATUS, 1]),
+            Transaction::transfer(&[0x06, 0], &[STATUS, 0b0000_0110]),
+        ]);
+        let quality = chip.link_quality().unwrap();
+        assert_eq!(quality.lost_packets(), 2);
+        assert_eq!(quality.retransmits(), 3);
+        assert!(quality.carrier_detected());
+        assert_eq!(quality.pa_level(), PALevel::Max);
+        spi.done();
+    }
+
+    #[test]
+    fn clear_interrupts_writes_selected_flags() {
+        let (mut chip, spi) = chip(&[Transaction::write(&[0x27, 0b0100_0000])]);
+        chip.clear_interrupts(Interrupts::new().data_ready())
+            .unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn mask_interrupts_writes_config() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x20, 0b0111_1110]),
+            Transaction::write(&[0x20, 0b0011_1110]),
+        ]);
+        chip.mask_interrupts(Interrupts::all()).unwrap();
+        // Only data ready reaches the IRQ pin
+        chip.set_interrupts(Interrupts::new().data_ready()).unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn transfer_error_displays_human_readable_message() {
+        use std::string::ToString;
+        type Error = TransferError<Infallible, Infallible>;
+
+        let err: Error = TransferError::MaximumRetries;
+        assert_eq!(err.to_string(), "maximum retransmit attempts reached");
+        let err: Error = TransferError::InvalidAddressLength {
+            expected: 5,
+            got: 3,
+        };
+        assert_eq!(
+            err.to_string(),
+            "address is 3 bytes long, but the address width is 5 bytes"
+        );
+        let err: Error = TransferError::Config(ConfigError::InvalidWifiChannel(15));
+        assert_eq!(
+            err.to_string(),
+            "invalid configuration: WiFi channel 15 is outside of 1..=14"
+        );
+    }
+
+    #[test]
+    fn configure_receiver_opens_pipes_in_order() {
+        let (mut chip, spi) = chip(&[
+            Transaction::write(&[0x2c, b'2']),
+            Transaction::transfer(&[0x02, 0], &[STATUS, 0b0000_0001]),
+            Transaction::write(&[0x33, 32]),
+            Transaction::write(&[0x22, 0b0000_0101]),
+            Transaction::write(&[0x2b, b'1', b'N', b'o', b'd', b'e']),
+            Transaction::transfer(&[0x02, 0], &[STATUS, 0b0000_0101]),
+            Transaction::write(&[0x32, 32]),
+            Transaction::write(&[0x22, 0b0000_0111]),
+        ]);
+        assert!(matches!(
+            chip.configure_receiver(&[(DataPipe::DP2, b"2"), (DataPipe::DP1, b"2Node")]),
+            Err(TransferError::Config(ConfigError::DuplicateAddress(b'2')))
+        ));
+        assert!(matches!(
+            chip.configure_receiver(&[(DataPipe::DP2, b"2"), (DataPipe::DP1, b"1No")]),
+            Err(TransferError::InvalidAddressLength {
+                expected: 5,
+                got: 3
+            })
+        ));
+        chip.configure_receiver(&[(DataPipe::DP2, b"2"), (DataPipe::DP1, b"1Node")])
+            .unwrap();
+        spi.done();
+    }
+
+    #[test]
+    fn peek_header_keeps_payload_for_read() {
+        let (mut chip, spi) = chip(&[
+            // Payload on pipe 2
+            Transaction::transfer(&[0x61, 0, 0, 0, 0], &[0b0000_0100, 7, 2, 3, 4]),
+            // RX FIFO empty
+            Transaction::transfer(&[0xff], &[STATUS]),
+        ]);
+        chip.payload_size = PayloadSize::Static(4);
+        let mut header = [0; 2];
+        assert_eq!(chip.peek_header(1, &mut header).unwrap(), 1);
+        assert_eq!(header, [7, 0]);
+        assert_eq!(chip.peek_header(8, &mut header).unwrap(), 2);
+        assert_eq!(header, [7, 2]);
+        assert_eq!(chip.data_available_on_pipe().unwrap(), Some(DataPipe::DP2));
+        let mut buf = [0; 4];
+        assert_eq!(
+            chip.read_with_pipe(&mut buf).unwrap(),
+            Some((DataPipe::DP2, 4))
+        );
+        assert_eq!(buf, [7, 2, 3, 4]);
+        assert!(!chip.data_available().unwrap());
+        spi.done();
+    }
+}
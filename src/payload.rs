@@ -0,0 +1,58 @@
+//! Fixed size payloads.
+#[cfg(feature = "micro-fmt")]
+use ufmt::{uDebug, uWrite, Formatter};
+
+/// A payload with a length that is known at compile time.
+///
+/// Meant for static payload sizes, where every payload has the same length. Sending it with
+/// [`write_payload()`](struct.Nrf24l01.html#method.write_payload) checks that the length matches
+/// the configured payload size, instead of silently padding or rejecting a slice of the wrong
+/// length.
+///
+/// # Examples
+/// ```rust
+/// use nrf24_rs::Payload;
+///
+/// let payload = Payload::new(42.0f32.to_le_bytes());
+/// assert_eq!(payload.as_bytes().len(), 4);
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Payload<const N: usize>([u8; N]);
+
+impl<const N: usize> Payload<N> {
+    /// Length of the payload in bytes.
+    pub const LEN: usize = N;
+
+    /// Creates a payload from its bytes.
+    pub fn new(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+    /// Returns the bytes of the payload.
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+    /// Returns the bytes of the payload mutably.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8; N] {
+        &mut self.0
+    }
+    /// Consumes the payload and returns its bytes.
+    pub fn into_inner(self) -> [u8; N] {
+        self.0
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Payload<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+}
+
+#[cfg(feature = "micro-fmt")]
+impl<const N: usize> uDebug for Payload<N> {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        f.debug_tuple("Payload")?.field(&&self.0[..])?.finish()
+    }
+}
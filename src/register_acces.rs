@@ -1,4 +1,4 @@
-#[allow(dead_code, non_camel_case_types)]
+#[allow(dead_code, non_camel_case_types, clippy::upper_case_acronyms)]
 #[derive(Clone, Copy)]
 pub(crate) enum Register {
     CONFIG = 0x0,
@@ -35,6 +35,7 @@ impl Register {
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy)]
 pub(crate) enum Instruction {
     /// Read registers
@@ -46,10 +47,22 @@ pub(crate) enum Instruction {
     RRX = 0b0110_0001,
     /// Write TX-payload, used in TX mode.
     WTX = 0b1010_0000,
+    /// Write TX-payload without requesting an acknowledgement, used in TX mode.
+    /// Requires the `EN_DYN_ACK` bit in the FEATURE register.
+    WTXNOACK = 0b1011_0000,
+    /// Write payload to be transmitted together with ACK packet, used in RX mode.
+    /// Last 3 bits are the data pipe.
+    WACKPL = 0b1010_1000,
     /// Flush TX FIFO, used in TX mode.
     FTX = 0b1110_0001,
     /// Flush RX FIFO, used in RX mode.
     FRX = 0b1110_0010,
+    /// Reuse last transmitted payload, used in TX mode.
+    /// The payload is resent as long as CE is pulsed, until the TX FIFO is flushed or a new
+    /// payload is written.
+    REUSETX = 0b1110_0011,
+    /// Read RX payload width for the top payload in the RX FIFO.
+    RRXPLWID = 0b0110_0000,
     /// No operation. Might be used to read STATUS register.
     NOP = 0b1111_1111,
 }
@@ -1,5 +1,7 @@
 //! Status datastructures.
-use crate::config::DataPipe;
+use crate::config::{DataPipe, PALevel};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 #[cfg(feature = "micro-fmt")]
 use ufmt::{uDebug, uWrite, Formatter};
 
@@ -12,16 +14,42 @@ pub struct Status(u8);
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct FIFOStatus(u8);
 
+/// Wrapper around the CONFIG register.
+/// Shows the live state of the chip, rather than the configuration it was set up with.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ConfigRegister(u8);
+
+/// Wrapper around the transmit observe register.
+/// Holds the packet loss and retransmission counters, useful for monitoring link quality.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ObserveTx(u8);
+
+/// Snapshot of the link quality, combining the transmit observe register, the received power
+/// detector and the power amplifier level.
+/// See [`link_quality()`](../struct.Nrf24l01.html#method.link_quality).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct LinkQuality {
+    pub(crate) observe_tx: ObserveTx,
+    pub(crate) carrier: bool,
+    pub(crate) pa_level: PALevel,
+}
+
 impl Status {
     /// Create a status obj with all the flags turned on.
     pub fn flags() -> Self {
         Self(0b01110000)
     }
+    /// Interprets a raw status byte, e.g. the first byte clocked out during an SPI transaction.
+    pub fn from_raw(value: u8) -> Self {
+        Self(value)
+    }
     /// Returns the raw value represented by this struct.
     pub fn value(&self) -> u8 {
         self.0
     }
     /// Checks if the status is valid.
+    ///
+    /// Bit 7 of the status register is always 0, if it is set the SPI read went wrong.
     pub fn is_valid(&self) -> bool {
         (self.0 >> 7) & 1 == 0
     }
@@ -40,11 +68,12 @@ impl Status {
     }
     /// Returns data pipe number for the payload availbe for reading
     /// or None if RX FIFO is empty.
+    ///
+    /// The reserved value 6 can be read on a glitchy bus, and also returns None.
     pub fn data_pipe_available(&self) -> Option<DataPipe> {
         match (self.0 >> 1) & 0b111 {
             x @ 0..=5 => Some(x.into()),
-            6 => panic!(),
-            7 => None,
+            6 | 7 => None,
             _ => unreachable!(), // because we AND the value
         }
     }
@@ -54,24 +83,62 @@ impl Status {
     }
 }
 
+/// Set of interrupts that can be signaled on the IRQ pin.
+///
+/// Constructed through the builder pattern, and used both to configure the interrupts and to
+/// report which interrupts were triggered.
+///
+/// # Examples
+/// ```rust
+/// use nrf24_rs::status::{InterruptKind, Interrupts};
+///
+/// let interrupts = Interrupts::new().data_ready().transmission_fail();
+/// assert!(interrupts.contains(InterruptKind::DataReady));
+/// assert!(!interrupts.contains(InterruptKind::TransmissionOk));
+/// ```
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(from = "InterruptBits")
+)]
 pub struct Interrupts(u8);
 
+/// Deserialized value of [`Interrupts`], the bits that are not an interrupt are dropped.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(rename = "Interrupts")]
+struct InterruptBits(u8);
+
+#[cfg(feature = "serde")]
+impl From<InterruptBits> for Interrupts {
+    fn from(bits: InterruptBits) -> Self {
+        Self::from(bits.0)
+    }
+}
+
 impl Interrupts {
+    /// Creates an empty set of interrupts.
     pub fn new() -> Self {
         Self(0)
     }
+    /// Adds the interrupt for reaching the maximum number of retransmissions.
     pub fn transmission_fail(mut self) -> Self {
         self.0 |= InterruptKind::TransmissionFail as u8;
         self
     }
+    /// Adds the interrupt for a packet that was transmitted.
+    /// If auto acknowledgement is enabled, it is only triggered when the ACK is received.
     pub fn transmission_ok(mut self) -> Self {
         self.0 |= InterruptKind::TransmissionOk as u8;
         self
     }
+    /// Adds the interrupt for new data arriving in the RX FIFO.
     pub fn data_ready(mut self) -> Self {
         self.0 |= InterruptKind::DataReady as u8;
         self
     }
+    /// Creates a set containing all interrupts.
     pub fn all() -> Self {
         let mut x = Self::new();
         x.0 |= InterruptKind::TransmissionFail as u8
@@ -79,6 +146,7 @@ impl Interrupts {
             | InterruptKind::DataReady as u8;
         x
     }
+    /// Returns `true` if the interrupt is part of the set.
     pub fn contains(&self, irq: InterruptKind) -> bool {
         self.0 & irq as u8 >= 1
     }
@@ -93,15 +161,104 @@ impl From<u8> for Interrupts {
     }
 }
 
+#[cfg(feature = "micro-fmt")]
+impl uDebug for Interrupts {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        f.debug_struct("Interrupts")?
+            .field("Data ready", &self.contains(InterruptKind::DataReady))?
+            .field("Data sent", &self.contains(InterruptKind::TransmissionOk))?
+            .field(
+                "Reached max retries",
+                &self.contains(InterruptKind::TransmissionFail),
+            )?
+            .finish()
+    }
+}
+
+/// The different interrupts of the chip, with their bit in the STATUS and CONFIG registers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InterruptKind {
+    /// Maximum number of retransmissions reached (`MAX_RT`).
     TransmissionFail = 0b0001_0000,
+    /// Data sent (`TX_DS`).
     TransmissionOk = 0b0010_0000,
+    /// Data ready in the RX FIFO (`RX_DR`).
     DataReady = 0b0100_0000,
 }
 
+/// Outcome of a transmission that was waited for, see
+/// [`write_timeout()`](../struct.Nrf24l01.html#method.write_timeout).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum WriteOutcome {
+    /// The payload was sent and an acknowledgement was received.
+    Acknowledged,
+    /// The payload was sent, but auto acknowledgement is disabled on data pipe 0, so no
+    /// acknowledgement was expected. There is no confirmation that it was received.
+    SentNoAck,
+}
+
+impl WriteOutcome {
+    /// Returns `true` if the receiver acknowledged the payload.
+    pub fn is_acknowledged(&self) -> bool {
+        *self == Self::Acknowledged
+    }
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for WriteOutcome {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        match *self {
+            Self::Acknowledged => f.write_str("Acknowledged"),
+            Self::SentNoAck => f.write_str("Sent without acknowledgement"),
+        }
+    }
+}
+
+/// Which events are signaled on the IRQ pin, decoded from the mask bits in the CONFIG register.
+///
+/// A field is `true` when the event pulls the IRQ pin low, i.e. when its mask bit is cleared.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct IrqPinConfig {
+    /// Data ready in the RX FIFO (`RX_DR`) is signaled on the pin.
+    pub rx_dr_on_pin: bool,
+    /// Data sent (`TX_DS`) is signaled on the pin.
+    pub tx_ds_on_pin: bool,
+    /// Maximum number of retransmissions reached (`MAX_RT`) is signaled on the pin.
+    pub max_rt_on_pin: bool,
+}
+
+impl From<Interrupts> for IrqPinConfig {
+    fn from(masked: Interrupts) -> Self {
+        Self {
+            rx_dr_on_pin: !masked.contains(InterruptKind::DataReady),
+            tx_ds_on_pin: !masked.contains(InterruptKind::TransmissionOk),
+            max_rt_on_pin: !masked.contains(InterruptKind::TransmissionFail),
+        }
+    }
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for IrqPinConfig {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        f.debug_struct("IrqPinConfig")?
+            .field("Data ready on pin", &self.rx_dr_on_pin)?
+            .field("Data sent on pin", &self.tx_ds_on_pin)?
+            .field("Reached max retries on pin", &self.max_rt_on_pin)?
+            .finish()
+    }
+}
+
 impl FIFOStatus {
-    /// Returns `true` if there are availbe locations in transmission queue
+    /// Returns `true` if there are no available locations in the transmission queue
     pub fn tx_full(&self) -> bool {
         (self.0 >> 5) & 1 != 0
     }
@@ -111,7 +268,7 @@ impl FIFOStatus {
         (self.0 >> 4) & 1 != 0
     }
 
-    /// Returns `true` if there are availbe locations in receive queue
+    /// Returns `true` if there are no available locations in the receive queue
     pub fn rx_full(&self) -> bool {
         (self.0 >> 1) & 1 != 0
     }
@@ -122,6 +279,110 @@ impl FIFOStatus {
     }
 }
 
+impl ConfigRegister {
+    /// Returns the raw value represented by this struct.
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+    /// Returns `true` if the chip is powered up (`PWR_UP`).
+    pub fn powered_up(&self) -> bool {
+        (self.0 >> 1) & 1 != 0
+    }
+    /// Returns `true` if the chip is configured as receiver (`PRIM_RX`).
+    pub fn is_rx(&self) -> bool {
+        self.0 & 1 != 0
+    }
+    /// Returns `true` if the CRC is enabled (`EN_CRC`).
+    pub fn crc_enabled(&self) -> bool {
+        (self.0 >> 3) & 1 != 0
+    }
+    /// Returns the interrupts that are masked, and will not be reflected on the IRQ pin.
+    pub fn masked_interrupts(&self) -> Interrupts {
+        Interrupts::from(self.0)
+    }
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for ConfigRegister {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        f.debug_struct("ConfigRegister")?
+            .field("Powered up", &self.powered_up())?
+            .field("Receiver", &self.is_rx())?
+            .field("CRC enabled", &self.crc_enabled())?
+            .field("Masked interrupts", &self.masked_interrupts())?
+            .finish()
+    }
+}
+
+impl ObserveTx {
+    /// Returns the number of lost packets.
+    ///
+    /// A packet is lost when it reached the maximum number of retransmissions.
+    /// The counter saturates at 15 and is only reset by writing the channel,
+    /// see [`reset_packet_loss()`](../struct.Nrf24l01.html#method.reset_packet_loss).
+    pub fn lost_packets(&self) -> u8 {
+        self.0 >> 4
+    }
+
+    /// Returns the number of retransmissions of the last packet.
+    ///
+    /// The counter is reset when the transmission of a new packet starts.
+    pub fn retransmits(&self) -> u8 {
+        self.0 & 0b0000_1111
+    }
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for ObserveTx {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        f.debug_struct("ObserveTx")?
+            .field("lost packets", &self.lost_packets())?
+            .field("retransmits", &self.retransmits())?
+            .finish()
+    }
+}
+
+impl LinkQuality {
+    /// Returns the number of lost packets, see [`ObserveTx::lost_packets()`].
+    pub fn lost_packets(&self) -> u8 {
+        self.observe_tx.lost_packets()
+    }
+    /// Returns the number of retransmissions of the last packet, see
+    /// [`ObserveTx::retransmits()`].
+    pub fn retransmits(&self) -> u8 {
+        self.observe_tx.retransmits()
+    }
+    /// Returns `true` if the received power detector detected a signal above -64 dBm.
+    pub fn carrier_detected(&self) -> bool {
+        self.carrier
+    }
+    /// Returns the power amplifier level.
+    pub fn pa_level(&self) -> PALevel {
+        self.pa_level
+    }
+}
+
+#[cfg(feature = "micro-fmt")]
+impl uDebug for LinkQuality {
+    fn fmt<W: ?Sized>(&self, f: &mut Formatter<'_, W>) -> core::result::Result<(), W::Error>
+    where
+        W: uWrite,
+    {
+        f.debug_struct("LinkQuality")?
+            .field("lost packets", &self.lost_packets())?
+            .field("retransmits", &self.retransmits())?
+            .field("carrier detected", &self.carrier)?
+            .field("power amplification level", &self.pa_level)?
+            .finish()
+    }
+}
+
 impl From<u8> for Status {
     fn from(t: u8) -> Self {
         Status(t)
@@ -134,6 +395,18 @@ impl From<u8> for FIFOStatus {
     }
 }
 
+impl From<u8> for ConfigRegister {
+    fn from(t: u8) -> Self {
+        ConfigRegister(t)
+    }
+}
+
+impl From<u8> for ObserveTx {
+    fn from(t: u8) -> Self {
+        ObserveTx(t)
+    }
+}
+
 impl core::fmt::Debug for Status {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         if !&self.is_valid() {